@@ -0,0 +1,59 @@
+//! Criterion benchmarks for the check and check digit
+//! paths. Run with
+//!
+//!     cargo bench
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use luhn::{luhn_check, luhn_check_bytes, luhn_check_fast, luhn_digit};
+
+fn bench_check(c: &mut Criterion) {
+    // Alternating digits and spaces: every other char is
+    // skipped.
+    let pathological = "0 ".repeat(500);
+    let inputs = [
+        ("16 digits", "4263 9826 4026 9299"),
+        ("19 digits", "6011 0009 9013 9424 009"),
+        ("1000 chars", pathological.as_str()),
+    ];
+
+    let mut group = c.benchmark_group("luhn_check");
+    for (name, input) in inputs {
+        group.bench_function(name, |b| b.iter(|| luhn_check(black_box(input))));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("luhn_check_bytes");
+    for (name, input) in inputs {
+        let bytes = input.as_bytes();
+        group.bench_function(name, |b| b.iter(|| luhn_check_bytes(black_box(bytes))));
+    }
+    group.finish();
+}
+
+fn bench_short(c: &mut Criterion) {
+    // Common rejects, which `luhn_check_fast` bails out on
+    // before the main loop.
+    let inputs = [("empty", ""), ("space", " "), ("one digit", "7")];
+
+    let mut group = c.benchmark_group("short");
+    for (name, input) in inputs {
+        group.bench_function(format!("luhn_check {}", name), |b| {
+            b.iter(|| luhn_check(black_box(input)))
+        });
+        group.bench_function(format!("luhn_check_fast {}", name), |b| {
+            b.iter(|| luhn_check_fast(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_digit(c: &mut Criterion) {
+    c.bench_function("luhn_digit", |b| {
+        b.iter(|| luhn_digit(black_box("4263 9826 4026 929")))
+    });
+}
+
+criterion_group!(benches, bench_check, bench_short, bench_digit);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+//! Rough timing comparison of the `char` path
+//! ([luhn::luhn_check]) against the digit-value path
+//! ([luhn::luhn_check_iter]). Run with
+//!
+//!     cargo run --release --example iter_bench
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use luhn::{luhn_check, luhn_check_iter};
+
+const ROUNDS: usize = 1_000_000;
+
+fn main() {
+    let cc_number = "4263 9826 4026 9299";
+    let digits: Vec<u32> = cc_number.chars().filter_map(|c| c.to_digit(10)).collect();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        assert!(luhn_check(black_box(cc_number)).unwrap());
+    }
+    let str_time = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        assert!(luhn_check_iter(black_box(&digits).iter().copied()).unwrap());
+    }
+    let iter_time = start.elapsed();
+
+    println!("luhn_check:      {:?}", str_time);
+    println!("luhn_check_iter: {:?}", iter_time);
+}
@@ -0,0 +1,31 @@
+//! Feeds arbitrary UTF-8 to [luhn::luhn_check] and
+//! [luhn::luhn_digit]. Neither may panic, and any
+//! `NonDigit`, `NonAsciiDigit` or `UnexpectedWhitespace`
+//! position must index the reported `char`. Run with
+//!
+//!     cargo +nightly fuzz run luhn_check
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use luhn::{luhn_check, luhn_digit, LuhnError};
+
+fn check_position(input: &str, e: LuhnError) {
+    if let LuhnError::NonDigit(i, c)
+    | LuhnError::NonAsciiDigit(i, c)
+    | LuhnError::UnexpectedWhitespace(i, c) = e
+    {
+        assert!(input.is_char_boundary(i));
+        assert_eq!(Some(c), input[i..].chars().next());
+    }
+}
+
+fuzz_target!(|input: &str| {
+    if let Err(e) = luhn_check(input) {
+        check_position(input, e);
+    }
+    match luhn_digit(input) {
+        Ok(d) => assert!(d.is_ascii_digit()),
+        Err(e) => check_position(input, e),
+    }
+});
@@ -1,44 +1,608 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
 /// Error type returned when the string passed to [luhn] is
 /// ill-formed.
-#[derive(Debug)]
+///
+/// Errors are ordered by variant, in declaration order,
+/// and then by their fields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum LuhnError {
-    /// Encountered a given non-digit `char` at given position.
+    /// Encountered a given non-digit `char` at given
+    /// position. The position is the byte offset of the
+    /// `char` in the input string, so it can be used
+    /// directly to slice the input.
     NonDigit(usize, char),
-    /// Given length was too short.
+    /// Given length was too short. Input with no digits at
+    /// all is reported as `Empty` instead.
     Short(usize),
+    /// Given length was too long.
+    TooLong(usize),
+    /// Encountered a given out-of-range digit value at given
+    /// position.
+    BadDigit(usize, u32),
+    /// Input was well-formed, but the check digit was wrong.
+    CheckFailed,
+    /// Input was well-formed, but had the given wrong check
+    /// digit where the given one was expected.
+    CheckDigit { expected: char, found: char },
+    /// Found the given number of wildcards where exactly one
+    /// was required.
+    Wildcards(usize),
+    /// Found the given `actual` number of digits where
+    /// `expected` were required.
+    WrongLength { expected: usize, actual: usize },
+    /// Found a separator at the given position at the start
+    /// or end of the input.
+    EdgeSeparator(usize),
+    /// Found a run of two or more separators starting at the
+    /// given position, or input at the given position that
+    /// breaks a required grouping.
+    BadGrouping(usize),
+    /// Input had no digits at all.
+    Empty,
+    /// Input did not match a formatting mask at the given
+    /// position.
+    MaskMismatch(usize),
+    /// Encountered a given non-ASCII decimal digit `char`,
+    /// such as an Arabic-Indic or fullwidth digit, at given
+    /// position. As with `NonDigit`, the position is a byte
+    /// offset.
+    NonAsciiDigit(usize, char),
+    /// Input did not start with the required prefix.
+    PrefixMismatch,
+    /// Encountered a given whitespace `char` that is not an
+    /// accepted separator, such as a non-breaking space, at
+    /// given position. As with `NonDigit`, the position is a
+    /// byte offset.
+    UnexpectedWhitespace(usize, char),
+    /// An output buffer was too small: the given number of
+    /// bytes was needed.
+    BufferTooSmall(usize),
+    /// The given check digit `index`, counting digits only
+    /// from 0, was past the end of input with the given
+    /// number of digits.
+    CheckIndex { index: usize, ndigits: usize },
 }
 
-fn luhn_sum(cc_number: &str) -> Result<(usize, [u32; 2]), LuhnError> {
-    fn doubled(d: u32) -> u32 {
-        if d > 4 {
-            1 + 2 * d - 10
-        } else {
-            2 * d
+impl fmt::Display for LuhnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LuhnError::NonDigit(i, c) => {
+                write!(f, "non-digit {:?} at position {}", c, i)
+            }
+            LuhnError::Short(n) => {
+                write!(f, "input too short: only {} valid digit(s)", n)
+            }
+            LuhnError::TooLong(n) => {
+                write!(f, "input too long: {} valid digit(s)", n)
+            }
+            LuhnError::BadDigit(i, d) => {
+                write!(f, "digit value {} out of range at position {}", d, i)
+            }
+            LuhnError::CheckFailed => write!(f, "check digit test failed"),
+            LuhnError::CheckDigit { expected, found } => write!(
+                f,
+                "wrong check digit: expected {:?}, found {:?}",
+                expected, found,
+            ),
+            LuhnError::Wildcards(n) => {
+                write!(f, "expected exactly one wildcard, found {}", n)
+            }
+            LuhnError::WrongLength { expected, actual } => {
+                write!(f, "expected {} digit(s), found {}", expected, actual)
+            }
+            LuhnError::EdgeSeparator(i) => {
+                write!(f, "separator at edge of input, position {}", i)
+            }
+            LuhnError::BadGrouping(i) => {
+                write!(f, "repeated separator at position {}", i)
+            }
+            LuhnError::Empty => write!(f, "no digits provided"),
+            LuhnError::MaskMismatch(i) => {
+                write!(f, "input does not match mask at position {}", i)
+            }
+            LuhnError::NonAsciiDigit(i, c) => {
+                write!(f, "non-ASCII digit {:?} at position {}", c, i)
+            }
+            LuhnError::PrefixMismatch => write!(f, "missing required prefix"),
+            LuhnError::UnexpectedWhitespace(i, c) => {
+                write!(f, "unexpected whitespace {:?} at position {}", c, i)
+            }
+            LuhnError::BufferTooSmall(n) => {
+                write!(f, "output buffer too small: {} byte(s) needed", n)
+            }
+            LuhnError::CheckIndex { index, ndigits } => write!(
+                f,
+                "check digit index {} out of range for {} digit(s)",
+                index, ndigits,
+            ),
+        }
+    }
+}
+
+impl core::error::Error for LuhnError {}
+
+/// Converts to an `InvalidData` I/O error wrapping the
+/// `LuhnError`, so that `?` works on Luhn operations in
+/// functions returning `std::io::Result`. The I/O error
+/// displays as the `LuhnError` does.
+#[cfg(feature = "std")]
+impl From<LuhnError> for std::io::Error {
+    fn from(e: LuhnError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl LuhnError {
+    /// A small integer identifying the variant, for storage
+    /// or interop where only integers are available. These
+    /// codes are stable: existing assignments will not
+    /// change, and new variants get new codes.
+    ///
+    /// | Code | Variant                |
+    /// |------|------------------------|
+    /// | 1    | `NonDigit`             |
+    /// | 2    | `Short`                |
+    /// | 3    | `TooLong`              |
+    /// | 4    | `BadDigit`             |
+    /// | 5    | `CheckFailed`          |
+    /// | 6    | `CheckDigit`           |
+    /// | 7    | `Wildcards`            |
+    /// | 8    | `WrongLength`          |
+    /// | 9    | `EdgeSeparator`        |
+    /// | 10   | `BadGrouping`          |
+    /// | 11   | `Empty`                |
+    /// | 12   | `MaskMismatch`         |
+    /// | 13   | `NonAsciiDigit`        |
+    /// | 14   | `PrefixMismatch`       |
+    /// | 15   | `UnexpectedWhitespace` |
+    /// | 16   | `BufferTooSmall`       |
+    /// | 17   | `CheckIndex`           |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use luhn::LuhnError;
+    /// assert_eq!(2, LuhnError::Short(1).code());
+    /// assert_eq!(Some("input too short"), LuhnError::from_code(2));
+    /// ```
+    pub fn code(&self) -> u32 {
+        match self {
+            LuhnError::NonDigit(..) => 1,
+            LuhnError::Short(_) => 2,
+            LuhnError::TooLong(_) => 3,
+            LuhnError::BadDigit(..) => 4,
+            LuhnError::CheckFailed => 5,
+            LuhnError::CheckDigit { .. } => 6,
+            LuhnError::Wildcards(_) => 7,
+            LuhnError::WrongLength { .. } => 8,
+            LuhnError::EdgeSeparator(_) => 9,
+            LuhnError::BadGrouping(_) => 10,
+            LuhnError::Empty => 11,
+            LuhnError::MaskMismatch(_) => 12,
+            LuhnError::NonAsciiDigit(..) => 13,
+            LuhnError::PrefixMismatch => 14,
+            LuhnError::UnexpectedWhitespace(..) => 15,
+            LuhnError::BufferTooSmall(_) => 16,
+            LuhnError::CheckIndex { .. } => 17,
+        }
+    }
+
+    /// A short human-readable label for the variant with the
+    /// given [code](LuhnError::code), or `None` if no
+    /// variant has that code.
+    pub fn from_code(code: u32) -> Option<&'static str> {
+        let label = match code {
+            1 => "non-digit",
+            2 => "input too short",
+            3 => "input too long",
+            4 => "digit value out of range",
+            5 => "check digit test failed",
+            6 => "wrong check digit",
+            7 => "wrong number of wildcards",
+            8 => "wrong number of digits",
+            9 => "separator at edge of input",
+            10 => "repeated separator",
+            11 => "no digits provided",
+            12 => "input does not match mask",
+            13 => "non-ASCII digit",
+            14 => "missing required prefix",
+            15 => "unexpected whitespace",
+            16 => "output buffer too small",
+            17 => "check digit index out of range",
+            _ => return None,
+        };
+        Some(label)
+    }
+}
+
+/// Compute the raw sums underlying the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm).
+/// Requires that the input be a string over the alphabet of
+/// ASCII digits and spaces.
+///
+/// Returns the count of valid digits (spaces are not
+/// counted) together with two alternating sums. `sums[0]`
+/// is the sum with the digits at even positions (0, 2, 4…,
+/// counting digits only, from the left) doubled; `sums[1]`
+/// is the sum with the digits at odd positions doubled.
+/// Doubling here means the Luhn "double and add the
+/// digits". The sums are reduced modulo 10 as they are
+/// computed, so each is below 10 however long the input.
+///
+/// A number with `ndigits` digits is valid when
+/// `sums[ndigits % 2]` is divisible by 10. The check digit
+/// to append to a body of `ndigits` digits is determined by
+/// `sums[1 - ndigits % 2]`.
+///
+/// Spaces are skipped wherever they appear, including at
+/// the start and end of the input, and positions count
+/// digits only. So for input over digits and spaces,
+/// inserting or removing spaces anywhere never changes the
+/// result, nor that of [luhn_check] or [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_sum;
+/// let (ndigits, sums) = luhn_sum("7 51").unwrap();
+/// assert_eq!(3, ndigits);
+///
+/// // "751" is not itself a valid number.
+/// assert_ne!(0, sums[ndigits % 2] % 10);
+///
+/// // The check digit for "751" is 8.
+/// let digit = (10 - sums[1 - ndigits % 2] % 10) % 10;
+/// assert_eq!(8, digit);
+/// ```
+pub fn luhn_sum(cc_number: impl AsRef<str>) -> Result<(usize, [u32; 2]), LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_sum_skip(cc_number, |c| c == ' ')
+}
+
+/// As with [luhn_sum], but for digits in the given `radix`,
+/// as read by `char::to_digit(radix)`: in base 16, `a` to
+/// `f` (or `A` to `F`) are digits. Doubling a digit `d`
+/// gives the sum of the base-`radix` digits of `2 * d`, as
+/// with [luhn_check_mod_n]. The sums are reduced modulo
+/// `radix`. A number with `ndigits` digits is valid when
+/// `sums[ndigits % 2]` is 0, and `luhn_sum_radix(s, 10)` is
+/// `luhn_sum(s)`.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in `2..=36`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_sum_radix;
+/// let (ndigits, sums) = luhn_sum_radix("DEADBEE3", 16).unwrap();
+/// assert_eq!(0, sums[ndigits % 2]);
+/// ```
+pub fn luhn_sum_radix(input: impl AsRef<str>, radix: u32) -> Result<(usize, [u32; 2]), LuhnError> {
+    assert!(
+        (2..=36).contains(&radix),
+        "luhn_sum_radix: radix must be in 2..=36, got {}",
+        radix,
+    );
+    let input = input.as_ref();
+    mod_n_sum(input, radix, |c| c.to_digit(radix), |c| c == ' ')
+}
+
+/// The [luhn_sum] of a number, with named fields, as
+/// returned by [luhn_partial].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LuhnPartial {
+    /// The number of digits.
+    pub count: usize,
+    /// The sum modulo 10 with the digits at even positions
+    /// (0, 2, 4…, counting digits only, from the left)
+    /// doubled: `sums[0]` of [luhn_sum].
+    pub even_sum: u32,
+    /// The sum modulo 10 with the digits at odd positions
+    /// doubled: `sums[1]` of [luhn_sum].
+    pub odd_sum: u32,
+}
+
+/// As with [luhn_sum], but with the results in a
+/// [LuhnPartial] so that the sums are named. A number with
+/// an even `count` passes [luhn_check] when `even_sum` is
+/// divisible by 10, and one with an odd `count` when
+/// `odd_sum` is. The check digit to append comes from the
+/// other sum.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_partial, LuhnPartial};
+/// let partial = luhn_partial("15").unwrap();
+/// assert_eq!(LuhnPartial { count: 2, even_sum: 7, odd_sum: 2 }, partial);
+/// // "15" has an even count, so its check digit comes
+/// // from odd_sum.
+/// assert_eq!(8, (10 - partial.odd_sum % 10) % 10);
+/// ```
+pub fn luhn_partial(cc_number: impl AsRef<str>) -> Result<LuhnPartial, LuhnError> {
+    let (count, [even_sum, odd_sum]) = luhn_sum(cc_number)?;
+    Ok(LuhnPartial {
+        count,
+        even_sum,
+        odd_sum,
+    })
+}
+
+/// The [luhn_partial] of a prefix shared by many numbers,
+/// such as an IIN, to be computed once and passed to
+/// [luhn_check_with_prefix] for each suffix.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_with_prefix, luhn_prefix_sum};
+/// let iin = luhn_prefix_sum("4263 98").unwrap();
+/// assert!(luhn_check_with_prefix(&iin, "26 4026 9299").unwrap());
+/// ```
+pub fn luhn_prefix_sum(prefix: impl AsRef<str>) -> Result<LuhnPartial, LuhnError> {
+    luhn_partial(prefix)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], of a prefix
+/// followed by `suffix`, given the [luhn_prefix_sum] of the
+/// prefix. The result is that of [luhn_check] on the
+/// concatenation, but only `suffix` is scanned.
+///
+/// A suffix digit at position `k` in `suffix` is at
+/// position `prefix_sum.count + k` in the whole number. So
+/// when the prefix has an odd number of digits, the suffix
+/// sums swap places before being added to the prefix sums.
+/// Errors are as with [luhn_check], with `NonDigit`
+/// positions given within `suffix`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_with_prefix, luhn_prefix_sum};
+/// let prefix = luhn_prefix_sum("751").unwrap();
+/// assert!(luhn_check_with_prefix(&prefix, "8").unwrap());
+/// assert!(!luhn_check_with_prefix(&prefix, "9").unwrap());
+/// ```
+pub fn luhn_check_with_prefix(
+    prefix_sum: &LuhnPartial,
+    suffix: impl AsRef<str>,
+) -> Result<bool, LuhnError> {
+    let (ndigits, sums) = luhn_sum(suffix)?;
+    let swap = prefix_sum.count % 2;
+    let combined = [
+        prefix_sum.even_sum % 10 + sums[swap],
+        prefix_sum.odd_sum % 10 + sums[1 - swap],
+    ];
+    check_sums(prefix_sum.count + ndigits, combined)
+}
+
+/// The index into the [luhn_sum] sums that [luhn_check]
+/// tests for `cc_number`: `ndigits % 2`. Input requirements
+/// and errors are as with [luhn_check].
+///
+/// # Parity
+///
+/// The check digit is the rightmost digit and is never
+/// doubled; doubling then alternates leftward. `luhn_sum`
+/// counts positions from the left, so whether the check
+/// digit is at an even or odd position depends on the
+/// number of digits. With `ndigits` digits, the check digit
+/// is at position `ndigits - 1`, and the doubled digits are
+/// at the positions with the same parity as `ndigits`:
+/// those are the digits doubled in `sums[ndigits % 2]`.
+///
+/// [luhn_digit] computes a digit to be appended, which
+/// will be at position `ndigits` of the body. The body's
+/// doubled digits are then those with the parity of
+/// `ndigits + 1`, so it uses `sums[1 - ndigits % 2]`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{check_parity, luhn_sum};
+/// // "7518": 7 and 1 are doubled, at positions 0 and 2.
+/// assert_eq!(0, check_parity("7518").unwrap());
+/// let (_, sums) = luhn_sum("7518").unwrap();
+/// assert_eq!(0, sums[0] % 10);
+///
+/// // "158": 5 is doubled, at position 1.
+/// assert_eq!(1, check_parity("158").unwrap());
+/// ```
+pub fn check_parity(cc_number: impl AsRef<str>) -> Result<usize, LuhnError> {
+    let ndigits = digit_count(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    Ok(ndigits % 2)
+}
+
+/// Count the valid digits in the input, as reported by
+/// [luhn_sum]. Spaces are skipped, and any other `char` is
+/// reported as with [luhn_check]. No check digit test is
+/// done.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::digit_count;
+/// assert_eq!(Ok(16), digit_count("4263 9826 4026 9299"));
+/// ```
+pub fn digit_count(cc_number: impl AsRef<str>) -> Result<usize, LuhnError> {
+    let (ndigits, _) = luhn_sum(cc_number)?;
+    Ok(ndigits)
+}
+
+/// Check only that `cc_number` is over the alphabet of
+/// ASCII digits and spaces, returning the number of digits
+/// as with [digit_count] but without computing any sums.
+/// A bad `char` is reported exactly as [luhn_check] reports
+/// it. No check digit test is done.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_scan};
+/// assert_eq!(Ok(16), luhn_scan("4263 9826 4026 9299"));
+/// assert_eq!(luhn_check("0 foo").unwrap_err(), luhn_scan("0 foo").unwrap_err());
+/// ```
+pub fn luhn_scan(cc_number: impl AsRef<str>) -> Result<usize, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut ndigits = 0;
+    for (i, c) in cc_number.char_indices() {
+        match c {
+            ' ' => (),
+            '0'..='9' => ndigits += 1,
+            _ => return Err(non_digit(i, c)),
         }
     }
+    Ok(ndigits)
+}
+
+/// How many more digits `cc_number` needs to reach
+/// `target_len` digits, or 0 if it already has that many or
+/// more. Digits are counted as with [digit_count], and
+/// errors are as with [digit_count]; no check digit test is
+/// done, and empty input is allowed.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::digits_remaining;
+/// assert_eq!(Ok(4), digits_remaining("4263 9826 4026", 16));
+/// assert_eq!(Ok(0), digits_remaining("4263 9826 4026 9299", 16));
+/// ```
+pub fn digits_remaining(cc_number: impl AsRef<str>, target_len: usize) -> Result<usize, LuhnError> {
+    let ndigits = digit_count(cc_number)?;
+    Ok(target_len.saturating_sub(ndigits))
+}
+
+/// Iterate over the digit values of `cc_number` in order,
+/// skipping spaces. The first non-digit is yielded as an
+/// error, as with [luhn_check], after which the iterator
+/// ends.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{digits_iter, LuhnError};
+/// let digits: Vec<_> = digits_iter("1 2x3").collect();
+/// assert_eq!(vec![Ok(1), Ok(2), Err(LuhnError::NonDigit(3, 'x'))], digits);
+/// ```
+pub fn digits_iter(cc_number: &str) -> impl Iterator<Item = Result<u32, LuhnError>> + '_ {
+    let mut done = false;
+    cc_number
+        .char_indices()
+        .filter(|&(_, c)| c != ' ')
+        .map_while(move |(i, c)| {
+            if done {
+                return None;
+            }
+            match c.to_digit(10) {
+                Some(d) => Some(Ok(d)),
+                None => {
+                    done = true;
+                    Some(Err(non_digit(i, c)))
+                }
+            }
+        })
+}
+
+// Reduce `sums` modulo `n` once they get large. Each digit
+// adds only a small amount to each sum (less than `n` for
+// Luhn, up to 27 for the GS1 weight of 3), and
+// `SUM_LIMIT` leaves about 2^31 of headroom above it. So
+// reducing whenever a sum passes `SUM_LIMIT` keeps them
+// from overflowing however long the input, without a
+// division for every digit.
+const SUM_LIMIT: u32 = u32::MAX / 2;
+
+const fn reduce_sums(sums: &mut [u32; 2], n: u32) {
+    if sums[0] > SUM_LIMIT || sums[1] > SUM_LIMIT {
+        sums[0] %= n;
+        sums[1] %= n;
+    }
+}
+
+// Luhn "doubling" of each digit: double it, then add the
+// digits of the result. `DOUBLED[d] == digit_sum(2 * d)`.
+const DOUBLED: [u32; 10] = [0, 2, 4, 6, 8, 1, 3, 5, 7, 9];
+
+/// The sum of the decimal digits of `n`: `digit_sum(18)` is
+/// `9`. The Luhn "doubling" of a digit `d` is
+/// `digit_sum(2 * d)`, which for a single digit is `2 * d`
+/// or `2 * d - 9`; other check digit schemes use the sum in
+/// its general form.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::digit_sum;
+/// assert_eq!(9, digit_sum(18));
+/// assert_eq!(1, digit_sum(10));
+/// assert_eq!(0, digit_sum(0));
+/// ```
+pub fn digit_sum(mut n: u32) -> u32 {
+    let mut sum = 0;
+    while n > 0 {
+        sum += n % 10;
+        n /= 10;
+    }
+    sum
+}
 
+// As with `luhn_sum`, but skipping each `char` for which
+// `skip` is true rather than just spaces.
+fn luhn_sum_skip(
+    cc_number: &str,
+    skip: impl Fn(char) -> bool,
+) -> Result<(usize, [u32; 2]), LuhnError> {
     // Idea: Compute both the "odd" and "even" sums and
     // return them both, along with a count of valid characters.
     // This can be done without heap, and can be used for
     // both checking and generating a check digit.
+    mod_n_sum(cc_number, 10, |c| c.to_digit(10), skip)
+}
+
+// The error for a `char` that is not accepted as a digit:
+// `NonAsciiDigit` if it is a non-ASCII decimal digit,
+// `UnexpectedWhitespace` if it is whitespace, and
+// otherwise `NonDigit`. An ASCII digit rejected in some
+// position, such as `8` in octal, is `NonDigit`.
+fn non_digit(i: usize, c: char) -> LuhnError {
+    if !c.is_ascii() && unicode_digit(c).is_some() {
+        LuhnError::NonAsciiDigit(i, c)
+    } else if c.is_whitespace() {
+        LuhnError::UnexpectedWhitespace(i, c)
+    } else {
+        LuhnError::NonDigit(i, c)
+    }
+}
+
+fn sum_digits(digits: impl Iterator<Item = u32>) -> (usize, [u32; 2]) {
     let mut valid = 0;
     let mut sums = [0; 2];
-    for (i, c) in cc_number.chars().enumerate() {
-        if c == ' ' {
-            continue;
-        }
-        if let Some(d) = c.to_digit(10) {
-            let m = valid % 2;
-            sums[1 - m] += d;
-            sums[m] += doubled(d);
-            valid += 1;
-            continue;
-        }
-        return Err(LuhnError::NonDigit(i, c));
+    for d in digits {
+        let m = valid % 2;
+        sums[1 - m] += d;
+        sums[m] += DOUBLED[d as usize];
+        reduce_sums(&mut sums, 10);
+        valid += 1;
     }
-    Ok((valid, sums))
+    (valid, sums.map(|sum| sum % 10))
 }
 
 /// Implementation of the [Luhn
@@ -48,6 +612,10 @@ fn luhn_sum(cc_number: &str) -> Result<(usize, [u32; 2]), LuhnError> {
 /// `false` if the check digit is wrong or if the input is
 /// not valid.
 ///
+/// The input is a complete number, body and check digit,
+/// so it needs at least two digits. A single digit is
+/// reported as `Short`, and no digits as `Empty`.
+///
 /// # Examples
 ///
 /// ```
@@ -56,80 +624,6199 @@ fn luhn_sum(cc_number: &str) -> Result<(usize, [u32; 2]), LuhnError> {
 /// assert!(luhn_check("513").unwrap());
 /// assert!(luhn_check("7518").unwrap());
 /// ```
-pub fn luhn_check(cc_number: &str) -> Result<bool, LuhnError> {
-    let (ndigits, sums) = luhn_sum(cc_number)?;
-    if ndigits < 2 {
-        return Err(LuhnError::Short(ndigits));
-    }
-    let check = sums[ndigits % 2];
-    Ok(check % 10 == 0)
+pub fn luhn_check(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_check_skip(cc_number, |c| c == ' ')
 }
 
 /// Implementation of the [Luhn
 /// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
-/// check digit test. Requires that the input be a string
-/// over the alphabet of ASCII digits and spaces. Returns
-/// `false` if the check digit is wrong or if the input is
-/// not valid.
+/// check digit test, as with [luhn_check], collapsed to a
+/// `bool`: `true` only for a well-formed number that
+/// passes. This loses information, since ill-formed input,
+/// input too short to check and a wrong check digit all
+/// give `false`; use [luhn_check] to tell them apart.
 ///
 /// # Examples
 ///
 /// ```
-/// # use luhn::luhn_digit;
-/// assert_eq!('8', luhn_digit("15").unwrap());
-/// assert_eq!('3', luhn_digit("51").unwrap());
-/// assert_eq!('8', luhn_digit("751").unwrap());
+/// # use luhn::luhn_is_valid;
+/// assert!(luhn_is_valid("158"));
+/// assert!(!luhn_is_valid("157"));
+/// assert!(!luhn_is_valid("foo"));
 /// ```
-pub fn luhn_digit(cc_number: &str) -> Result<char, LuhnError> {
-    let (ndigits, sums) = luhn_sum(cc_number)?;
-    if ndigits == 0 {
-        return Err(LuhnError::Short(ndigits));
+pub fn luhn_is_valid(cc_number: impl AsRef<str>) -> bool {
+    luhn_check(cc_number) == Ok(true)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, with the same results as [luhn_check]
+/// for every input. Input shorter than two bytes cannot
+/// hold the two digits a valid number needs, so it is
+/// rejected up front without running the main loop.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_fast};
+/// assert!(luhn_check_fast("158").unwrap());
+/// assert_eq!(luhn_check("7"), luhn_check_fast("7"));
+/// assert_eq!(luhn_check(""), luhn_check_fast(""));
+/// ```
+pub fn luhn_check_fast(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    match *cc_number.as_bytes() {
+        [] | [b' '] => Err(LuhnError::Empty),
+        [b'0'..=b'9'] => Err(LuhnError::Short(1)),
+        [b] => Err(non_digit(0, b as char)),
+        _ => luhn_check(cc_number),
     }
-    let check = sums[1 - ndigits % 2];
-    let residue = check % 10;
-    let digit = (10 - residue) % 10;
-    Ok(char::from_digit(digit, 10).unwrap())
 }
 
-#[test]
-fn test_non_digit_cc_number() {
-    assert!(matches!(
-        luhn_check("foo"),
-        Err(LuhnError::NonDigit(0, 'f')),
-    ));
-    assert!(matches!(
-        luhn_check("0 foo 0"),
-        Err(LuhnError::NonDigit(2, 'f')),
-    ));
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check]. Any `char` in
+/// `seps` is skipped wherever it appears in the input,
+/// instead of just spaces.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_with_separators;
+/// assert!(luhn_check_with_separators("4263-9826-4026-9299", &['-']).unwrap());
+/// assert!(luhn_check_with_separators("7-5 1 8", &['-', ' ']).unwrap());
+/// ```
+pub fn luhn_check_with_separators(
+    cc_number: impl AsRef<str>,
+    seps: &[char],
+) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_check_skip(cc_number, |c| seps.contains(&c))
 }
 
-#[test]
-fn test_empty_cc_number() {
-    assert!(matches!(luhn_check(""), Err(LuhnError::Short(0))));
-    assert!(matches!(luhn_check(" "), Err(LuhnError::Short(0))));
-    assert!(matches!(luhn_check("  "), Err(LuhnError::Short(0))));
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for ID numbers
+/// written with hyphens such as `123-45-6789`. Both spaces
+/// and ASCII hyphens are skipped; any other `char` is
+/// reported as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_id;
+/// assert!(luhn_check_id("7-51 8").unwrap());
+/// ```
+pub fn luhn_check_id(id: impl AsRef<str>) -> Result<bool, LuhnError> {
+    luhn_check_with_separators(id, &[' ', '-'])
 }
 
-#[test]
-fn test_single_digit_cc_number() {
-    assert!(matches!(luhn_check("0"), Err(LuhnError::Short(1))));
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for a grouped
+/// numeric token such as `1234-5678-9012-3452`, of the form
+/// produced by `luhn_token`. Hyphens and spaces are skipped
+/// wherever they appear; any other `char` is reported as
+/// with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_token;
+/// assert!(luhn_check_token("4263-9826-4026-9299").unwrap());
+/// ```
+pub fn luhn_check_token(token: impl AsRef<str>) -> Result<bool, LuhnError> {
+    luhn_check_with_separators(token, &[' ', '-'])
 }
 
-#[test]
-fn test_two_digit_cc_number() {
-    assert!(luhn_check(" 0 0 ").unwrap());
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for codes such
+/// as `GC-12345674` with a fixed non-numeric prefix before
+/// the number. `code` must start with `prefix`, or the
+/// result is `PrefixMismatch`. A single hyphen right after
+/// the prefix is skipped, and the rest is checked as with
+/// [luhn_check]; `NonDigit` positions are byte offsets in
+/// `code`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_prefixed, LuhnError};
+/// assert!(luhn_check_prefixed("GC-12345674", "GC-").unwrap());
+/// assert!(luhn_check_prefixed("GC-12345674", "GC").unwrap());
+/// assert_eq!(
+///     Err(LuhnError::PrefixMismatch),
+///     luhn_check_prefixed("GX-12345674", "GC-"),
+/// );
+/// ```
+pub fn luhn_check_prefixed(
+    code: impl AsRef<str>,
+    prefix: impl AsRef<str>,
+) -> Result<bool, LuhnError> {
+    let code = code.as_ref();
+    let rest = code
+        .strip_prefix(prefix.as_ref())
+        .ok_or(LuhnError::PrefixMismatch)?;
+    let rest = rest.strip_prefix('-').unwrap_or(rest);
+    let offset = code.len() - rest.len();
+    luhn_check(rest).map_err(|e| match e {
+        LuhnError::NonDigit(i, c) => LuhnError::NonDigit(offset + i, c),
+        LuhnError::NonAsciiDigit(i, c) => LuhnError::NonAsciiDigit(offset + i, c),
+        LuhnError::UnexpectedWhitespace(i, c) => LuhnError::UnexpectedWhitespace(offset + i, c),
+        e => e,
+    })
 }
 
-#[test]
-fn test_valid_cc_number() {
-    assert!(luhn_check("4263 9826 4026 9299").unwrap());
-    assert!(luhn_check("4539 3195 0343 6467").unwrap());
-    assert!(luhn_check("7992 7398 713").unwrap());
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for a number
+/// taken from a CSV or TSV cell. Spaces, commas and tabs
+/// are all skipped as separators; any other `char` is
+/// reported as with [luhn_check], with its byte offset in
+/// `cell`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_csv_cell;
+/// assert!(luhn_check_csv_cell("4263,9826,4026,9299").unwrap());
+/// assert!(luhn_check_csv_cell("4263\t9826\t4026\t9299").unwrap());
+/// ```
+pub fn luhn_check_csv_cell(cell: impl AsRef<str>) -> Result<bool, LuhnError> {
+    luhn_check_with_separators(cell, &[' ', ',', '\t'])
 }
 
-#[test]
-fn test_invalid_cc_number() {
-    assert!(!luhn_check("4223 9826 4026 9299").unwrap());
-    assert!(!luhn_check("4539 3195 0343 6476").unwrap());
-    assert!(!luhn_check("8273 1232 7352 0569").unwrap());
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for numbers in
+/// the European style grouped with periods, such as
+/// `4263.9826.4026.9299`. Spaces, periods and non-breaking
+/// spaces (U+00A0) are all skipped; any other `char` is
+/// reported as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_eu;
+/// assert!(luhn_check_eu("4263.9826.4026.9299").unwrap());
+/// assert!(luhn_check_eu("4263\u{00A0}9826 4026.9299").unwrap());
+/// ```
+pub fn luhn_check_eu(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    luhn_check_with_separators(cc_number, &[' ', '.', '\u{00A0}'])
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that the
+/// position in `NonDigit` is the ordinal of the bad `char`
+/// among the non-space `char`s. The count starts at 0 and
+/// does not include spaces. [luhn_check] reports a byte
+/// offset in the whole input instead.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_pos, LuhnError};
+/// assert_eq!(Err(LuhnError::NonDigit(4, 'x')), luhn_check("0 0 x"));
+/// assert_eq!(Err(LuhnError::NonDigit(2, 'x')), luhn_check_pos("0 0 x"));
+/// ```
+pub fn luhn_check_pos(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_check(cc_number).map_err(|e| match e {
+        LuhnError::NonDigit(i, c) => {
+            let ordinal = cc_number[..i].chars().filter(|&c| c != ' ').count();
+            LuhnError::NonDigit(ordinal, c)
+        }
+        LuhnError::NonAsciiDigit(i, c) => {
+            let ordinal = cc_number[..i].chars().filter(|&c| c != ' ').count();
+            LuhnError::NonAsciiDigit(ordinal, c)
+        }
+        LuhnError::UnexpectedWhitespace(i, c) => {
+            let ordinal = cc_number[..i].chars().filter(|&c| c != ' ').count();
+            LuhnError::UnexpectedWhitespace(ordinal, c)
+        }
+        e => e,
+    })
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that any
+/// Unicode whitespace (`char::is_whitespace`) is skipped
+/// rather than only ASCII space. This accepts numbers
+/// pasted with non-breaking or other Unicode spaces between
+/// groups.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_unicode_ws};
+/// let s = "7\u{00A0}51\u{202F}8";
+/// assert!(luhn_check_unicode_ws(s).unwrap());
+/// assert!(luhn_check(s).is_err());
+/// ```
+pub fn luhn_check_unicode_ws(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_check_skip(cc_number, char::is_whitespace)
+}
+
+// The zero of each run of ten Unicode decimal digits
+// (`Numeric_Type=Decimal`), as of Unicode 14.0. Each run is
+// contiguous and in order, so a digit's value is its offset
+// from the zero.
+const UNICODE_ZEROS: [u32; 66] = [
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6, 0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66,
+    0xDE6, 0xE50, 0xED0, 0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0, 0x11650,
+    0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x16A60, 0x16AC0, 0x16B50,
+    0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E950, 0x1FBF0,
+];
+
+// Value of a Unicode decimal digit, if `c` is one.
+fn unicode_digit(c: char) -> Option<u32> {
+    let c = c as u32;
+    let i = UNICODE_ZEROS.partition_point(|&z| z <= c);
+    let d = c - UNICODE_ZEROS.get(i.checked_sub(1)?)?;
+    (d < 10).then_some(d)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that any
+/// Unicode decimal digit is accepted with its numeric
+/// value: fullwidth `'１'` or Arabic-Indic `'١'` counts as
+/// `1`. Spaces are skipped; any other `char` is reported
+/// as with [luhn_check]. [luhn_check] stays ASCII-only.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_unicode_digits};
+/// assert!(luhn_check_unicode_digits("１５８").unwrap());
+/// assert!(luhn_check_unicode_digits("٧٥١٨").unwrap());
+/// assert!(luhn_check("１５８").is_err());
+/// ```
+pub fn luhn_check_unicode_digits(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    for (i, c) in cc_number.char_indices() {
+        if c != ' ' && unicode_digit(c).is_none() {
+            return Err(non_digit(i, c));
+        }
+    }
+    let digits = cc_number.chars().filter_map(unicode_digit);
+    let (ndigits, sums) = sum_digits(digits);
+    check_sums(ndigits, sums)
+}
+
+// Digit value of `c`, also reading the letters commonly
+// typed for digits as those digits.
+fn forgiving_digit(c: char) -> Option<u32> {
+    match c {
+        'O' | 'o' => Some(0),
+        'l' | 'I' => Some(1),
+        'S' => Some(5),
+        'B' => Some(8),
+        _ => c.to_digit(10),
+    }
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that
+/// letters commonly typed in place of digits are read as
+/// those digits:
+///
+/// | Letters    | Digit |
+/// |------------|-------|
+/// | `O`, `o`   | 0     |
+/// | `l`, `I`   | 1     |
+/// | `S`        | 5     |
+/// | `B`        | 8     |
+///
+/// Spaces are skipped; any other non-digit is reported as
+/// with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_forgiving};
+/// assert!(luhn_check_forgiving("4O12 8888 8888 l88l").unwrap());
+/// assert!(luhn_check("4O12 8888 8888 l88l").is_err());
+/// ```
+pub fn luhn_check_forgiving(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    for (i, c) in cc_number.char_indices() {
+        if c != ' ' && forgiving_digit(c).is_none() {
+            return Err(non_digit(i, c));
+        }
+    }
+    let digits = cc_number.chars().filter_map(forgiving_digit);
+    let (ndigits, sums) = sum_digits(digits);
+    check_sums(ndigits, sums)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that any
+/// `char` for which `skip` returns `true` is skipped. Any
+/// other `char` is reported as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_skip;
+/// let s = "(4263) 9826-4026-9299";
+/// assert!(luhn_check_skip(s, |c| c.is_ascii_punctuation() || c == ' ').unwrap());
+/// ```
+pub fn luhn_check_skip(
+    cc_number: impl AsRef<str>,
+    skip: impl Fn(char) -> bool,
+) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum_skip(cc_number, skip)?;
+    check_sums(ndigits, sums)
+}
+
+/// The residue modulo 10 of the checked [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// sum: `0` for a number that passes, and how far off the
+/// sum is otherwise. `luhn_check(s)` is `Ok(true)` exactly
+/// when `luhn_residue(s)` is `Ok(0)`. Input requirements and
+/// errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_residue;
+/// assert_eq!(0, luhn_residue("158").unwrap());
+/// assert_eq!(9, luhn_residue("157").unwrap());
+/// ```
+pub fn luhn_residue(cc_number: impl AsRef<str>) -> Result<u32, LuhnError> {
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    Ok(sums[ndigits % 2] % 10)
+}
+
+/// A one-line description of how `cc_number` fares under
+/// [luhn_check], for logs and error pages: `"valid"`,
+/// `"invalid (residue N)"` with its [luhn_residue] if the
+/// check digit is wrong, or the `LuhnError` message if the
+/// input is ill-formed.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_explain;
+/// assert_eq!("valid", luhn_explain("158"));
+/// assert_eq!("invalid (residue 9)", luhn_explain("157"));
+/// assert_eq!("non-digit 'x' at position 1", luhn_explain("1x8"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_explain(cc_number: impl AsRef<str>) -> String {
+    use alloc::string::ToString;
+
+    match luhn_residue(cc_number) {
+        Ok(0) => String::from("valid"),
+        Ok(residue) => alloc::format!("invalid (residue {})", residue),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// `Empty` if there are no digits, otherwise `Short`.
+fn too_short(ndigits: usize) -> LuhnError {
+    if ndigits == 0 {
+        LuhnError::Empty
+    } else {
+        LuhnError::Short(ndigits)
+    }
+}
+
+fn check_sums(ndigits: usize, sums: [u32; 2]) -> Result<bool, LuhnError> {
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    let check = sums[ndigits % 2];
+    Ok(check.is_multiple_of(10))
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check]. Rather than
+/// returning `false` for a wrong check digit, returns
+/// `CheckDigit` with the check digit that was expected and
+/// the one that was found.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_verify, LuhnError};
+/// assert!(luhn_verify("158").is_ok());
+/// assert_eq!(
+///     Err(LuhnError::CheckDigit { expected: '8', found: '0' }),
+///     luhn_verify("150"),
+/// );
+/// ```
+pub fn luhn_verify(cc_number: impl AsRef<str>) -> Result<(), LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (expected, found) = check_digit_pair(cc_number)?;
+    if expected != found {
+        return Err(LuhnError::CheckDigit { expected, found });
+    }
+    Ok(())
+}
+
+/// Given a complete number including its check digit,
+/// return the check digit it should have to pass the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. This differs from [luhn_digit], which
+/// takes the number *without* its check digit: here the
+/// last digit is replaced rather than appended. Input
+/// requirements and errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_digit, luhn_expected_digit};
+/// assert_eq!('9', luhn_expected_digit("4263 9826 4026 9290").unwrap());
+/// assert_eq!('9', luhn_digit("4263 9826 4026 929").unwrap());
+/// ```
+pub fn luhn_expected_digit(cc_number: impl AsRef<str>) -> Result<char, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (expected, _) = check_digit_pair(cc_number)?;
+    Ok(expected)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test for a number stored as a body and a
+/// separate check digit. This is the same as checking
+/// `body` with `check` appended, without building the
+/// string. A `check` that is not an ASCII digit is reported
+/// as `NonDigit` at the position just past the end of
+/// `body`. Other input requirements and errors are as with
+/// [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_split;
+/// assert!(luhn_check_split("15", '8').unwrap());
+/// assert!(!luhn_check_split("15", '7').unwrap());
+/// ```
+pub fn luhn_check_split(body: impl AsRef<str>, check: char) -> Result<bool, LuhnError> {
+    let body = body.as_ref();
+    let expected = luhn_digit(body)?;
+    if !check.is_ascii_digit() {
+        return Err(LuhnError::NonDigit(body.len(), check));
+    }
+    Ok(expected == check)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for formats that
+/// put the check digit somewhere other than the end. The
+/// digit at `check_index`, counting digits only from 0 and
+/// skipping spaces, is the check digit, and the other
+/// digits in order are the body. With `check_index` at the
+/// last digit this is [luhn_check].
+///
+/// A `check_index` with no digit there is reported as
+/// `CheckIndex` with the index and the number of digits.
+/// Other errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_at;
+/// assert!(luhn_check_at("8751", 0).unwrap());
+/// assert!(luhn_check_at("7581", 1).unwrap());
+/// assert!(luhn_check_at("7518", 3).unwrap());
+/// ```
+pub fn luhn_check_at(cc_number: impl AsRef<str>, check_index: usize) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let ndigits = luhn_scan(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    if check_index >= ndigits {
+        return Err(LuhnError::CheckIndex {
+            index: check_index,
+            ndigits,
+        });
+    }
+    let digits = cc_number.chars().filter_map(|c| c.to_digit(10));
+    let check = digits.clone().nth(check_index).unwrap();
+    let body = digits
+        .enumerate()
+        .filter(|&(i, _)| i != check_index)
+        .map(|(_, d)| d);
+    let (body_ndigits, sums) = sum_digits(body);
+    Ok(u32::from(digit_sums(body_ndigits, sums)?) == check)
+}
+
+/// The check digit `cc_number` should have, and the one it
+/// has.
+fn check_digit_pair(cc_number: &str) -> Result<(char, char), LuhnError> {
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    let found = cc_number.chars().rev().find(|&c| c != ' ').unwrap();
+    let d = found.to_digit(10).unwrap();
+    // The check digit is never doubled, so removing it from
+    // the checked sum leaves the contribution of the body.
+    let body = (sums[ndigits % 2] + 10 - d) % 10;
+    let e = (10 - body) % 10;
+    Ok((char::from_digit(e, 10).unwrap(), found))
+}
+
+/// Result of [luhn_status].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuhnStatus {
+    /// The number passes the check digit test.
+    Valid,
+    /// The number is well-formed, but its check digit is
+    /// `found` where `expected` would make it pass.
+    WrongCheckDigit { expected: char, found: char },
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check]. Rather than
+/// `false`, a wrong check digit gives `WrongCheckDigit`
+/// with the check digit that was expected and the one that
+/// was found. Errors are as with [luhn_check]. Nothing is
+/// allocated.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_status, LuhnStatus};
+/// assert_eq!(LuhnStatus::Valid, luhn_status("7518").unwrap());
+/// assert_eq!(
+///     LuhnStatus::WrongCheckDigit { expected: '8', found: '9' },
+///     luhn_status("7519").unwrap(),
+/// );
+/// ```
+pub fn luhn_status(cc_number: impl AsRef<str>) -> Result<LuhnStatus, LuhnError> {
+    let (expected, found) = check_digit_pair(cc_number.as_ref())?;
+    if expected == found {
+        Ok(LuhnStatus::Valid)
+    } else {
+        Ok(LuhnStatus::WrongCheckDigit { expected, found })
+    }
+}
+
+/// For a number that fails the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, the single-digit change closest to the
+/// end that makes it pass, as `(position, old, new)` with
+/// `position` the byte offset of the digit. A number that
+/// passes gives `None`. Errors are as with [luhn_check].
+///
+/// Every digit of a failing number has exactly one
+/// replacement that fixes it, since doubling permutes the
+/// digit values. So the closest change is always to the
+/// check digit itself, `None` is only returned for a number
+/// that passes, and the position of a typo elsewhere in the
+/// number cannot be determined from the number alone. See
+/// `luhn_suggest` for the fixes at every position.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_minimal_fix;
+/// assert_eq!(Some((3, '9', '8')), luhn_minimal_fix("7519").unwrap());
+/// assert_eq!(None, luhn_minimal_fix("7518").unwrap());
+/// ```
+pub fn luhn_minimal_fix(
+    cc_number: impl AsRef<str>,
+) -> Result<Option<(usize, char, char)>, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (expected, found) = check_digit_pair(cc_number)?;
+    if expected == found {
+        return Ok(None);
+    }
+    let position = cc_number.rfind(|c| c != ' ').unwrap();
+    Ok(Some((position, found, expected)))
+}
+
+/// The change to the check digit that makes a failing
+/// number pass, as with [luhn_minimal_fix], under a name
+/// that says what it changes.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_fix_check_digit;
+/// assert_eq!(Some((3, '9', '8')), luhn_fix_check_digit("7519").unwrap());
+/// ```
+pub fn luhn_fix_check_digit(
+    cc_number: impl AsRef<str>,
+) -> Result<Option<(usize, char, char)>, LuhnError> {
+    luhn_minimal_fix(cc_number)
+}
+
+/// Result of [luhn_diagnose].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuhnDiagnosis {
+    /// The number passes the check digit test.
+    Valid,
+    /// Swapping the digit at the given position with the
+    /// next digit would make the number valid.
+    Transposition(usize),
+    /// Replacing the digit at the given position with the
+    /// given digit would make the number valid.
+    SingleDigit { position: usize, digit: char },
+}
+
+/// Diagnose a number that may fail the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. Input requirements and errors are as
+/// with [luhn_check]. Positions are reported as for
+/// `NonDigit`: as byte offsets in the input.
+///
+/// The heuristic is:
+///
+/// 1. If the number is valid, report `Valid`.
+///
+/// 2. Otherwise, if swapping some pair of adjacent digits
+///    (ignoring spaces between them) makes the number valid,
+///    report `Transposition` with the position of the first
+///    digit of the leftmost such pair. Luhn detects every
+///    adjacent transposition except `09`↔`90`, so this is
+///    the most specific explanation available.
+///
+/// 3. Otherwise, report `SingleDigit` with the position of
+///    the check digit and the digit it should have been.
+///    For *every* position there is exactly one replacement
+///    digit that makes the number valid, so the position of
+///    a single-digit error cannot be determined; the check
+///    digit is reported as the conventional fix.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_diagnose, LuhnDiagnosis};
+/// assert_eq!(LuhnDiagnosis::Valid, luhn_diagnose("7518").unwrap());
+/// assert_eq!(LuhnDiagnosis::Transposition(1), luhn_diagnose("7158").unwrap());
+/// assert_eq!(
+///     LuhnDiagnosis::SingleDigit { position: 18, digit: '9' },
+///     luhn_diagnose("4263 9826 4026 9295").unwrap(),
+/// );
+/// ```
+pub fn luhn_diagnose(cc_number: impl AsRef<str>) -> Result<LuhnDiagnosis, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    let total = sums[ndigits % 2];
+    if total % 10 == 0 {
+        return Ok(LuhnDiagnosis::Valid);
+    }
+
+    // A digit is doubled when an odd number of digits
+    // follow it.
+    let is_doubled = |k: usize| (ndigits - k).is_multiple_of(2);
+    let digits = cc_number
+        .char_indices()
+        .filter(|&(_, c)| c != ' ')
+        .map(|(i, c)| (i, c.to_digit(10).unwrap()));
+
+    let mut prev: Option<(usize, u32)> = None;
+    let mut last = (0, 0);
+    for (k, (i, d)) in digits.enumerate() {
+        if let Some((j, p)) = prev {
+            // Swapping p and d changes which one is doubled.
+            let (before, after) = if is_doubled(k - 1) {
+                (DOUBLED[p as usize] + d, DOUBLED[d as usize] + p)
+            } else {
+                (p + DOUBLED[d as usize], d + DOUBLED[p as usize])
+            };
+            // Both sides are at most 18, so adding 20 keeps
+            // the difference non-negative.
+            if (total % 10 + 20 + after - before) % 10 == 0 {
+                return Ok(LuhnDiagnosis::Transposition(j));
+            }
+        }
+        prev = Some((i, d));
+        last = (i, d);
+    }
+
+    let (position, d) = last;
+    let e = (10 - (total + 10 - d) % 10) % 10;
+    let digit = char::from_digit(e, 10).unwrap();
+    Ok(LuhnDiagnosis::SingleDigit { position, digit })
+}
+
+/// Whether `cc_number` has adjacent digits `09` or `90`
+/// (ignoring spaces). Swapping such a pair is the one
+/// adjacent transposition the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// cannot detect, so a number containing one may deserve a
+/// second look even when it passes. The whole input is
+/// scanned, and non-digits are reported as with
+/// [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{has_luhn_blind_spot, luhn_check};
+/// assert!(has_luhn_blind_spot("1 09").unwrap());
+/// assert_eq!(luhn_check("109"), luhn_check("190"));
+/// assert!(!has_luhn_blind_spot("158").unwrap());
+/// ```
+pub fn has_luhn_blind_spot(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut found = false;
+    let mut prev = None;
+    for d in digits_iter(cc_number) {
+        let d = d?;
+        found |= matches!((prev, d), (Some(0), 9) | (Some(9), 0));
+        prev = Some(d);
+    }
+    Ok(found)
+}
+
+/// Result of [luhn_classify].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuhnClass {
+    /// The number passes the check digit test, and matches
+    /// none of the patterns below.
+    Valid,
+    /// The number passes, but its digits run up or down by
+    /// one at each step, wrapping from 9 to 0 or 0 to 9.
+    ValidSequential,
+    /// The number passes, but is a single digit or a pair of
+    /// digits repeated throughout.
+    ValidRepeated,
+    /// The number fails the check digit test.
+    Invalid,
+}
+
+/// Classify a number by the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, flagging valid numbers that are
+/// obviously made up. The patterns detected, ignoring
+/// spaces, are:
+///
+/// * [LuhnClass::ValidRepeated]: one digit repeated, as in
+///   `0000 0000 0000 0000`, or a pair of digits repeated, as
+///   in the `4242 4242 4242 4242` test card.
+/// * [LuhnClass::ValidSequential]: digits stepping up or
+///   down by one, as in `3210 9876 5432 1098`.
+///
+/// Input requirements and errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_classify, LuhnClass};
+/// assert_eq!(LuhnClass::Valid, luhn_classify("4263 9826 4026 9299").unwrap());
+/// assert_eq!(LuhnClass::ValidRepeated, luhn_classify("4242 4242 4242 4242").unwrap());
+/// assert_eq!(LuhnClass::Invalid, luhn_classify("4223 9826 4026 9299").unwrap());
+/// ```
+pub fn luhn_classify(cc_number: impl AsRef<str>) -> Result<LuhnClass, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if !luhn_check(cc_number)? {
+        return Ok(LuhnClass::Invalid);
+    }
+    let digits = || cc_number.chars().filter_map(|c| c.to_digit(10));
+    if digits().zip(digits().skip(2)).all(|(a, b)| a == b) {
+        return Ok(LuhnClass::ValidRepeated);
+    }
+    let steps = || {
+        digits()
+            .zip(digits().skip(1))
+            .map(|(a, b)| (b + 10 - a) % 10)
+    };
+    if steps().all(|s| s == 1) || steps().all(|s| s == 9) {
+        return Ok(LuhnClass::ValidSequential);
+    }
+    Ok(LuhnClass::Valid)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], that also
+/// rejects numbers using fewer than `min_distinct` of the
+/// ten digit values, as obviously made up numbers tend to.
+/// Returns `true` only if the number passes and has enough
+/// distinct digits. Errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_entropy_ok;
+/// assert!(luhn_entropy_ok("4263 9826 4026 9299", 4).unwrap());
+/// assert!(!luhn_entropy_ok("0000 0000 0000 0000", 4).unwrap());
+/// ```
+pub fn luhn_entropy_ok(cc_number: impl AsRef<str>, min_distinct: usize) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if !luhn_check(cc_number)? {
+        return Ok(false);
+    }
+    let mut seen = [false; 10];
+    for d in cc_number.chars().filter_map(|c| c.to_digit(10)) {
+        seen[d as usize] = true;
+    }
+    let distinct = seen.iter().filter(|&&s| s).count();
+    Ok(distinct >= min_distinct)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that a
+/// number whose digits are all zero is rejected. Such a
+/// number always passes the plain check, but is never a
+/// real card number; here it gives `false` where
+/// [luhn_check] would give `true`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_check_strict};
+/// assert!(luhn_check_strict("158").unwrap());
+/// assert!(luhn_check("0000 0000 0000 0000").unwrap());
+/// assert!(!luhn_check_strict("0000 0000 0000 0000").unwrap());
+/// ```
+pub fn luhn_check_strict(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    let all_zero = cc_number.chars().all(|c| c == '0' || c == ' ');
+    Ok(check_sums(ndigits, sums)? && !all_zero)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that a
+/// space as the first or last character is reported as
+/// `EdgeSeparator` rather than skipped. Spaces between
+/// digits are still allowed.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_trimmed, LuhnError};
+/// assert!(luhn_check_trimmed("7 51 8").unwrap());
+/// assert_eq!(
+///     Err(LuhnError::EdgeSeparator(4)),
+///     luhn_check_trimmed("7518 "),
+/// );
+/// ```
+pub fn luhn_check_trimmed(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if cc_number.starts_with(' ') {
+        return Err(LuhnError::EdgeSeparator(0));
+    }
+    if cc_number.ends_with(' ') {
+        return Err(LuhnError::EdgeSeparator(cc_number.len() - 1));
+    }
+    luhn_check(cc_number)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], except that two
+/// or more consecutive spaces are reported as `BadGrouping`
+/// with the position of the first space in the run. Single
+/// spaces anywhere are still skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_grouping, LuhnError};
+/// assert!(luhn_check_grouping("4263 9826 4026 9299").unwrap());
+/// assert_eq!(
+///     Err(LuhnError::BadGrouping(4)),
+///     luhn_check_grouping("4263  9826 4026 9299"),
+/// );
+/// ```
+pub fn luhn_check_grouping(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if let Some(i) = cc_number.find("  ") {
+        return Err(LuhnError::BadGrouping(i));
+    }
+    luhn_check(cc_number)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for input that
+/// must be grouped exactly as `groups`: runs of ASCII
+/// digits of the given sizes, joined by single `sep`s, such
+/// as `&[4, 4, 4, 4]` with `' '` for the usual card layout.
+///
+/// A `sep` where a digit belongs, a digit where a `sep`
+/// belongs, or input that ends early or runs on, is
+/// reported as `BadGrouping` with the byte offset where the
+/// grouping breaks. Any other `char` is reported as with
+/// [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_groups, LuhnError};
+/// let groups = &[4, 4, 4, 4];
+/// assert!(luhn_check_groups("4263 9826 4026 9299", groups, ' ').unwrap());
+/// assert_eq!(
+///     Err(LuhnError::BadGrouping(8)),
+///     luhn_check_groups("4263 982 64026 9299", groups, ' '),
+/// );
+/// ```
+pub fn luhn_check_groups(
+    cc_number: impl AsRef<str>,
+    groups: &[usize],
+    sep: char,
+) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let end = cc_number.len();
+    let mut chars = cc_number.char_indices();
+    for (g, &len) in groups.iter().enumerate() {
+        if g > 0 {
+            match chars.next() {
+                Some((_, c)) if c == sep => (),
+                Some((i, _)) => return Err(LuhnError::BadGrouping(i)),
+                None => return Err(LuhnError::BadGrouping(end)),
+            }
+        }
+        for _ in 0..len {
+            match chars.next() {
+                Some((_, c)) if c.is_ascii_digit() => (),
+                Some((i, c)) if c == sep => return Err(LuhnError::BadGrouping(i)),
+                Some((i, c)) => return Err(non_digit(i, c)),
+                None => return Err(LuhnError::BadGrouping(end)),
+            }
+        }
+    }
+    if let Some((i, _)) = chars.next() {
+        return Err(LuhnError::BadGrouping(i));
+    }
+    luhn_check_with_separators(cc_number, &[sep])
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for input with a
+/// fixed format such as `"####-####-####-####"`. `input`
+/// must match `mask` `char` for `char`: each `placeholder`
+/// in `mask` must be an ASCII digit in `input`, and every
+/// other `char` must appear literally. The Luhn test is run
+/// on the digits at the placeholder positions.
+///
+/// A digit position holding a non-digit is reported as
+/// with [luhn_check]. A literal that does not match, or input that
+/// is longer or shorter than the mask, is reported as
+/// `MaskMismatch` with the byte offset in `input` where
+/// they differ.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_masked, LuhnError};
+/// let mask = "####-####-####-####";
+/// assert!(luhn_check_masked("4263-9826-4026-9299", mask, '#').unwrap());
+/// assert_eq!(
+///     Err(LuhnError::MaskMismatch(4)),
+///     luhn_check_masked("4263 9826 4026 9299", mask, '#'),
+/// );
+/// ```
+pub fn luhn_check_masked(
+    input: impl AsRef<str>,
+    mask: impl AsRef<str>,
+    placeholder: char,
+) -> Result<bool, LuhnError> {
+    let input = input.as_ref();
+    let mask_chars = mask.as_ref().chars();
+    let mut mask = mask_chars.clone();
+    for (i, c) in input.char_indices() {
+        match mask.next() {
+            Some(m) if m == placeholder => {
+                if !c.is_ascii_digit() {
+                    return Err(non_digit(i, c));
+                }
+            }
+            Some(m) if m == c => (),
+            _ => return Err(LuhnError::MaskMismatch(i)),
+        }
+    }
+    if mask.next().is_some() {
+        return Err(LuhnError::MaskMismatch(input.len()));
+    }
+    let digits = input
+        .chars()
+        .zip(mask_chars)
+        .filter(|&(_, m)| m == placeholder)
+        .map(|(c, _)| c as u32 - '0' as u32);
+    let (ndigits, sums) = sum_digits(digits);
+    check_sums(ndigits, sums)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for numbers that
+/// carry the check digit leftmost rather than rightmost.
+/// This is equivalent to reversing the digits before
+/// checking. Spaces are skipped, and `NonDigit` positions
+/// refer to the string as given.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_reversed;
+/// assert!(luhn_check_reversed("8157").unwrap());
+/// assert!(!luhn_check_reversed("7518").unwrap());
+/// ```
+pub fn luhn_check_reversed(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    // Counting from the left, the check digit is at position
+    // 0 and the doubled digits are at odd positions.
+    Ok(sums[1].is_multiple_of(10))
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for input that
+/// packs two numbers by interleaving their digits. Counting
+/// digits only, from 0 at the left and skipping spaces, the
+/// digits at even positions make up the first number and
+/// those at odd positions the second, each in order. Both
+/// numbers are checked and the two results returned.
+///
+/// Input is as for [luhn_check]. Each number needs at least
+/// two digits, so fewer than four in all is an error:
+/// `Short` or `Empty` with the digit count of the shorter
+/// number.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_interleaved;
+/// // "7518" and "158": 7 1 5 5 1 8 8.
+/// assert_eq!((true, true), luhn_check_interleaved("7155 188").unwrap());
+/// assert_eq!((true, false), luhn_check_interleaved("7155 178").unwrap());
+/// ```
+pub fn luhn_check_interleaved(cc_number: impl AsRef<str>) -> Result<(bool, bool), LuhnError> {
+    let cc_number = cc_number.as_ref();
+    luhn_scan(cc_number)?;
+    let digits = cc_number.chars().filter_map(|c| c.to_digit(10));
+    let (even_ndigits, even_sums) = sum_digits(digits.clone().step_by(2));
+    let (odd_ndigits, odd_sums) = sum_digits(digits.skip(1).step_by(2));
+    if odd_ndigits < 2 {
+        return Err(too_short(odd_ndigits));
+    }
+    Ok((
+        check_sums(even_ndigits, even_sums)?,
+        check_sums(odd_ndigits, odd_sums)?,
+    ))
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, with the same results as [luhn_check]
+/// for every input, written the textbook way: number the
+/// digits from the right starting at 1, so that the check
+/// digit is digit 1, double every even-numbered digit, and
+/// test that the total is a multiple of 10. [luhn_check]
+/// instead counts from the left, which gets the same
+/// answer without knowing the length in advance.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_from_right;
+/// assert!(luhn_check_from_right("7518").unwrap());
+/// assert!(!luhn_check_from_right("7519").unwrap());
+/// ```
+pub fn luhn_check_from_right(cc_number: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut ndigits = 0;
+    let mut total = 0;
+    // Keep the leftmost non-digit, as luhn_check reports.
+    let mut bad = None;
+    for (i, c) in cc_number.char_indices().rev() {
+        if c == ' ' {
+            continue;
+        }
+        let Some(d) = c.to_digit(10) else {
+            bad = Some(non_digit(i, c));
+            continue;
+        };
+        ndigits += 1;
+        if ndigits % 2 == 0 {
+            let doubled = 2 * d;
+            total += if doubled > 9 { doubled - 9 } else { doubled };
+        } else {
+            total += d;
+        }
+        if total > SUM_LIMIT {
+            total %= 10;
+        }
+    }
+    if let Some(e) = bad {
+        return Err(e);
+    }
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    Ok(total % 10 == 0)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for a number
+/// that must have exactly `expected_len` digits. Spaces do
+/// not count toward the length. Any other number of digits
+/// is reported as `WrongLength`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_len, LuhnError};
+/// assert!(luhn_check_len("4263 9826 4026 9299", 16).unwrap());
+/// assert_eq!(
+///     Err(LuhnError::WrongLength { expected: 15, actual: 16 }),
+///     luhn_check_len("4263 9826 4026 9299", 15),
+/// );
+/// ```
+pub fn luhn_check_len(cc_number: impl AsRef<str>, expected_len: usize) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    if ndigits != expected_len {
+        return Err(LuhnError::WrongLength {
+            expected: expected_len,
+            actual: ndigits,
+        });
+    }
+    check_sums(ndigits, sums)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for input of at
+/// most `max_digits` digits. The scan stops as soon as the
+/// limit is passed, reporting `TooLong` with the digit count
+/// at that point, so an overlong input is never read in
+/// full.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_bounded, LuhnError};
+/// assert!(luhn_check_bounded("4263 9826 4026 9299", 19).unwrap());
+/// assert_eq!(Err(LuhnError::TooLong(3)), luhn_check_bounded("7518", 2));
+/// ```
+pub fn luhn_check_bounded(
+    cc_number: impl AsRef<str>,
+    max_digits: usize,
+) -> Result<bool, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut state = LuhnState::new();
+    for c in cc_number.chars() {
+        state.push(c)?;
+        if state.ndigits > max_digits {
+            return Err(LuhnError::TooLong(state.ndigits));
+        }
+    }
+    state.is_valid()
+}
+
+/// Configurable [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, combining the options of the
+/// `luhn_check_*` variants. The default configuration
+/// behaves exactly like [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{LuhnError, LuhnValidator};
+/// let validator = LuhnValidator::new()
+///     .separators(&['-'])
+///     .require_len(16)
+///     .reject_all_zero(true);
+/// assert!(validator.check("4263-9826-4026-9299").unwrap());
+/// assert!(!validator.check("0000-0000-0000-0000").unwrap());
+/// assert_eq!(
+///     Err(LuhnError::UnexpectedWhitespace(4, ' ')),
+///     validator.check("4263 9826 4026 9299"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct LuhnValidator<'a> {
+    separators: &'a [char],
+    require_len: Option<usize>,
+    reject_all_zero: bool,
+    max_len: Option<usize>,
+}
+
+impl Default for LuhnValidator<'_> {
+    fn default() -> Self {
+        LuhnValidator {
+            separators: &[' '],
+            require_len: None,
+            reject_all_zero: false,
+            max_len: None,
+        }
+    }
+}
+
+impl<'a> LuhnValidator<'a> {
+    /// A validator that behaves like [luhn_check].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip each `char` in `seps` instead of just spaces, as
+    /// with [luhn_check_with_separators].
+    pub fn separators(mut self, seps: &'a [char]) -> Self {
+        self.separators = seps;
+        self
+    }
+
+    /// Require exactly `len` digits, as with
+    /// [luhn_check_len].
+    pub fn require_len(mut self, len: usize) -> Self {
+        self.require_len = Some(len);
+        self
+    }
+
+    /// Whether to fail all-zero input, as with
+    /// [luhn_check_strict].
+    pub fn reject_all_zero(mut self, reject: bool) -> Self {
+        self.reject_all_zero = reject;
+        self
+    }
+
+    /// Stop with `TooLong` once there are more than `len`
+    /// digits, as with [luhn_check_bounded].
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = Some(len);
+        self
+    }
+
+    /// Check `input` with the configured options.
+    pub fn check(&self, input: &str) -> Result<bool, LuhnError> {
+        let mut ndigits = 0;
+        let mut sums = [0; 2];
+        let mut all_zero = true;
+        for (i, c) in input.char_indices() {
+            if self.separators.contains(&c) {
+                continue;
+            }
+            let Some(d) = c.to_digit(10) else {
+                return Err(non_digit(i, c));
+            };
+            let m = ndigits % 2;
+            sums[1 - m] += d;
+            sums[m] += DOUBLED[d as usize];
+            reduce_sums(&mut sums, 10);
+            all_zero &= d == 0;
+            ndigits += 1;
+            if self.max_len.is_some_and(|max| ndigits > max) {
+                return Err(LuhnError::TooLong(ndigits));
+            }
+        }
+        if let Some(expected) = self.require_len {
+            if ndigits != expected {
+                return Err(LuhnError::WrongLength {
+                    expected,
+                    actual: ndigits,
+                });
+            }
+        }
+        let valid = check_sums(ndigits, sums)?;
+        Ok(valid && !(self.reject_all_zero && all_zero))
+    }
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test over a byte slice, as with
+/// [luhn_check]. Requires that the input be over the
+/// alphabet of ASCII digits and spaces: any other byte `c`
+/// is reported as [luhn_check] reports `c as char`, such
+/// as `NonDigit(i, c as char)`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_bytes;
+/// assert!(luhn_check_bytes(b"158").unwrap());
+/// assert!(luhn_check_bytes(b"7 518").unwrap());
+/// ```
+pub fn luhn_check_bytes(cc_number: &[u8]) -> Result<bool, LuhnError> {
+    let (ndigits, sums) = luhn_sum_bytes(cc_number)?;
+    check_sums(ndigits, sums)
+}
+
+/// Compute the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit value to append to the given number body,
+/// over a byte slice, as with [luhn_digit_value]. Input is
+/// as for [luhn_check_bytes].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_digit_bytes;
+/// assert_eq!(Ok(8), luhn_digit_bytes(b"15"));
+/// assert_eq!(Ok(8), luhn_digit_bytes(b"7 51"));
+/// ```
+pub fn luhn_digit_bytes(body: &[u8]) -> Result<u8, LuhnError> {
+    let (ndigits, sums) = luhn_sum_bytes(body)?;
+    digit_sums(ndigits, sums)
+}
+
+// As with `luhn_sum`, over bytes.
+fn luhn_sum_bytes(cc_number: &[u8]) -> Result<(usize, [u32; 2]), LuhnError> {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    for (i, &c) in cc_number.iter().enumerate() {
+        if c == b' ' {
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let d = (c - b'0') as u32;
+            let m = valid % 2;
+            sums[1 - m] += d;
+            sums[m] += DOUBLED[d as usize];
+            reduce_sums(&mut sums, 10);
+            valid += 1;
+            continue;
+        }
+        return Err(non_digit(i, c as char));
+    }
+    Ok((valid, sums.map(|sum| sum % 10)))
+}
+
+/// Compute the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit to append to the given number body.
+/// Requires that the input be a string over the alphabet
+/// of ASCII digits and spaces.
+///
+/// A number is its body followed by its check digit, so
+/// the body needs at least one digit: the completed number
+/// then has the two digits that [luhn_check] requires.
+/// A body with no digits is reported as `Empty`. Thus
+/// `luhn_digit("5")` succeeds where `luhn_check("5")` is
+/// `Short(1)`: here `"5"` is a body, there a complete
+/// number.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_digit;
+/// assert_eq!('8', luhn_digit("15").unwrap());
+/// assert_eq!('3', luhn_digit("51").unwrap());
+/// assert_eq!('8', luhn_digit("751").unwrap());
+/// ```
+pub fn luhn_digit(cc_number: impl AsRef<str>) -> Result<char, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let digit = luhn_digit_value(cc_number)?;
+    Ok(char::from_digit(digit as u32, 10).unwrap())
+}
+
+/// Compute the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit as with [luhn_digit], but return its value
+/// in `0..=9` rather than a `char`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_digit_value;
+/// assert_eq!(8, luhn_digit_value("15").unwrap());
+/// assert_eq!(3, luhn_digit_value("51").unwrap());
+/// ```
+pub fn luhn_digit_value(cc_number: impl AsRef<str>) -> Result<u8, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    digit_sums(ndigits, sums)
+}
+
+fn digit_sums(ndigits: usize, sums: [u32; 2]) -> Result<u8, LuhnError> {
+    let residue = checksum_sums(ndigits, sums)?;
+    let digit = (10 - residue) % 10;
+    Ok(digit as u8)
+}
+
+fn checksum_sums(ndigits: usize, sums: [u32; 2]) -> Result<u32, LuhnError> {
+    if ndigits == 0 {
+        return Err(LuhnError::Empty);
+    }
+    let check = sums[1 - ndigits % 2];
+    Ok(check % 10)
+}
+
+/// The residue modulo 10 of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// sum of a number body, from which [luhn_digit] derives
+/// the check digit as `(10 - checksum) % 10`. This is the
+/// sum over the body with the digits that will be doubled
+/// once the check digit is appended already doubled; adding
+/// the check digit then brings it to a multiple of 10, so
+/// [luhn_residue] of the completed number is 0. Input
+/// requirements and errors are as with [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_checksum, luhn_digit};
+/// assert_eq!(2, luhn_checksum("15").unwrap());
+/// assert_eq!('8', luhn_digit("15").unwrap());
+/// ```
+pub fn luhn_checksum(cc_number: impl AsRef<str>) -> Result<u32, LuhnError> {
+    let (ndigits, sums) = luhn_sum(cc_number)?;
+    checksum_sums(ndigits, sums)
+}
+
+/// Append the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit to the given partial number. Requires that
+/// the input be a string over the alphabet of ASCII digits
+/// and spaces. Spaces are stripped: the result is a bare
+/// string of digits.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_complete;
+/// assert_eq!("158", luhn_complete("15").unwrap());
+/// assert_eq!("7518", luhn_complete("7 51").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_complete(partial: impl AsRef<str>) -> Result<String, LuhnError> {
+    let partial = partial.as_ref();
+    let digit = luhn_digit(partial)?;
+    let mut result: String = partial.chars().filter(|&c| c != ' ').collect();
+    result.push(digit);
+    Ok(result)
+}
+
+/// As with `luhn_complete`, but without allocating: the
+/// completed digits are written to the start of `out`, and
+/// the number of bytes written is returned. If `out` is too
+/// short, nothing is written and the result is
+/// `BufferTooSmall` with the length needed.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_complete_into, LuhnError};
+/// let mut out = [0; 3];
+/// assert_eq!(Ok(3), luhn_complete_into("15", &mut out));
+/// assert_eq!(b"158", &out);
+/// assert_eq!(Err(LuhnError::BufferTooSmall(4)), luhn_complete_into("751", &mut out));
+/// ```
+pub fn luhn_complete_into(body: impl AsRef<str>, out: &mut [u8]) -> Result<usize, LuhnError> {
+    let body = body.as_ref();
+    let (ndigits, sums) = luhn_sum(body)?;
+    let digit = digit_sums(ndigits, sums)?;
+    let len = ndigits + 1;
+    if out.len() < len {
+        return Err(LuhnError::BufferTooSmall(len));
+    }
+    let digits = body.bytes().filter(|&b| b != b' ').chain([b'0' + digit]);
+    for (o, b) in out.iter_mut().zip(digits) {
+        *o = b;
+    }
+    Ok(len)
+}
+
+/// Every digit `d` such that `partial` followed by `d`
+/// passes [luhn_check], in increasing order. For any body
+/// there is exactly one such digit, the one [luhn_digit]
+/// computes, so the result always has one element; the
+/// `Vec` suits callers that offer a list of completions.
+/// Errors are as with [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::valid_next_digits;
+/// assert_eq!(vec!['8'], valid_next_digits("15").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn valid_next_digits(partial: impl AsRef<str>) -> Result<Vec<char>, LuhnError> {
+    Ok(vec![luhn_digit(partial)?])
+}
+
+/// Compute `count` successive [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digits for `cc_number`, for schemes with more than
+/// one check digit. Each digit is computed as with
+/// [luhn_digit] over the input with the digits before it
+/// appended, so `luhn_digits(s, 1)` is `luhn_digit(s)` as a
+/// string. Errors are as with [luhn_digit], including
+/// `Empty` for input with no digits even when `count` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_digits};
+/// assert_eq!("86", luhn_digits("15", 2).unwrap());
+/// assert!(luhn_check("1586").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_digits(cc_number: impl AsRef<str>, count: usize) -> Result<String, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut state = LuhnState::new();
+    for c in cc_number.chars() {
+        state.push(c)?;
+    }
+    if state.ndigits == 0 {
+        return Err(LuhnError::Empty);
+    }
+    let mut digits = String::with_capacity(count);
+    for _ in 0..count {
+        let d = state.check_digit()?;
+        state.push(d)?;
+        digits.push(d);
+    }
+    Ok(digits)
+}
+
+/// As with [luhn_check_bytes], but usable in `const`
+/// context. Returns `false` for any input that
+/// [luhn_check_bytes] would reject as an error, as well as
+/// for a wrong check digit.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_bytes_const;
+/// const VALID: bool = luhn_check_bytes_const(b"4263 9826 4026 9299");
+/// assert!(VALID);
+/// assert!(!luhn_check_bytes_const(b"0 foo"));
+/// ```
+pub const fn luhn_check_bytes_const(cc_number: &[u8]) -> bool {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    let mut i = 0;
+    while i < cc_number.len() {
+        let c = cc_number[i];
+        i += 1;
+        if c == b' ' {
+            continue;
+        }
+        if !c.is_ascii_digit() {
+            return false;
+        }
+        let d = (c - b'0') as u32;
+        let m = valid % 2;
+        sums[1 - m] += d;
+        sums[m] += DOUBLED[d as usize];
+        reduce_sums(&mut sums, 10);
+        valid += 1;
+    }
+    valid >= 2 && sums[valid % 2] % 10 == 0
+}
+
+/// As with [luhn_digit_value], but usable in `const`
+/// context, so that check digits can be computed at compile
+/// time. `body` must be ASCII digits and spaces, with at
+/// least one digit.
+///
+/// # Panics
+///
+/// Panics on a byte that is neither an ASCII digit nor a
+/// space, or if there are no digits. In `const` context
+/// this is a compile error.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_digit_byte_const;
+/// const D: u8 = luhn_digit_byte_const(b"15");
+/// assert_eq!(8, D);
+/// ```
+///
+/// ```compile_fail
+/// # use luhn::luhn_digit_byte_const;
+/// const D: u8 = luhn_digit_byte_const(b"1x");
+/// ```
+pub const fn luhn_digit_byte_const(body: &[u8]) -> u8 {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    let mut i = 0;
+    while i < body.len() {
+        let c = body[i];
+        i += 1;
+        if c == b' ' {
+            continue;
+        }
+        assert!(c.is_ascii_digit(), "non-digit in check digit body");
+        let d = (c - b'0') as u32;
+        let m = valid % 2;
+        sums[1 - m] += d;
+        sums[m] += DOUBLED[d as usize];
+        reduce_sums(&mut sums, 10);
+        valid += 1;
+    }
+    assert!(valid > 0, "no digits in check digit body");
+    ((10 - sums[1 - valid % 2] % 10) % 10) as u8
+}
+
+/// A string literal checked at compile time with
+/// [luhn_check_bytes_const]. Expands to the literal as a
+/// `&'static str`; a literal that fails the check, or is
+/// not well-formed, is a compile error.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn;
+/// const TEST_CARD: &str = luhn!("4263 9826 4026 9299");
+/// assert_eq!("4263 9826 4026 9299", TEST_CARD);
+/// ```
+///
+/// ```compile_fail
+/// # use luhn::luhn;
+/// let bad = luhn!("4263 9826 4026 9290");
+/// ```
+#[macro_export]
+macro_rules! luhn {
+    ($number:literal) => {{
+        const NUMBER: &str = $number;
+        const _: () = assert!(
+            $crate::luhn_check_bytes_const(NUMBER.as_bytes()),
+            "literal fails the Luhn check",
+        );
+        NUMBER
+    }};
+}
+
+/// Implementation of the [Luhn mod N
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_mod_N_algorithm)
+/// check test, a generalization of the Luhn Algorithm to an
+/// alphabet of `n` symbols. `codepoint` maps each input
+/// `char` to its value in `0..n`, returning `None` for
+/// `char`s outside the alphabet; these are reported as
+/// `NonDigit`, or as `UnexpectedWhitespace` or
+/// `NonAsciiDigit` if they are whitespace or non-ASCII
+/// digits. Spaces are skipped as with [luhn_check].
+///
+/// The standard algorithm doubles a digit `d` and adds the
+/// decimal digits of the result. Here the doubled value is
+/// instead `(2 * d) % n + (2 * d) / n` — the sum of the
+/// base-`n` digits of `2 * d` — and the total must be
+/// divisible by `n` rather than 10. For `n = 10` the two
+/// agree, so `luhn_check_mod_n(s, 10, |c| c.to_digit(10))`
+/// is the same as `luhn_check(s)`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_mod_n;
+/// let hex = |c: char| c.to_digit(16);
+/// assert!(luhn_check_mod_n("DEADBEE3", 16, hex).unwrap());
+/// assert!(!luhn_check_mod_n("DEADBEE4", 16, hex).unwrap());
+/// assert!(luhn_check_mod_n("7518", 10, |c| c.to_digit(10)).unwrap());
+/// ```
+pub fn luhn_check_mod_n(
+    input: impl AsRef<str>,
+    n: u32,
+    codepoint: impl Fn(char) -> Option<u32>,
+) -> Result<bool, LuhnError> {
+    let input = input.as_ref();
+    let (valid, sums) = mod_n_sum(input, n, codepoint, |c| c == ' ')?;
+    if valid < 2 {
+        return Err(too_short(valid));
+    }
+    Ok(sums[valid % 2].is_multiple_of(n))
+}
+
+/// Compute the [Luhn mod N
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_mod_N_algorithm)
+/// check character for the given body, as described for
+/// [luhn_check_mod_n]. `to_val` maps input `char`s to their
+/// values as `codepoint` does there; `from_val` maps the
+/// computed check value in `0..n` back to a `char`, and
+/// should be the inverse of `to_val`. Input requirements and
+/// errors are as with [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_mod_n_digit;
+/// let to_val = |c: char| c.to_digit(16);
+/// let from_val = |d| char::from_digit(d, 16).unwrap().to_ascii_uppercase();
+/// assert_eq!('3', luhn_mod_n_digit("DEADBEE", 16, to_val, from_val).unwrap());
+/// ```
+pub fn luhn_mod_n_digit(
+    input: impl AsRef<str>,
+    n: u32,
+    to_val: impl Fn(char) -> Option<u32>,
+    from_val: impl Fn(u32) -> char,
+) -> Result<char, LuhnError> {
+    let input = input.as_ref();
+    let (ndigits, sums) = mod_n_sum(input, n, to_val, |c| c == ' ')?;
+    if ndigits == 0 {
+        return Err(LuhnError::Empty);
+    }
+    let check = sums[1 - ndigits % 2];
+    Ok(from_val((n - check % n) % n))
+}
+
+/// Check a mod-10 check digit with an arbitrary repeating
+/// weight pattern. The rightmost digit is the check digit
+/// and has weight 1. Moving left from the digit before it,
+/// the digits are multiplied by `weights[0]`, `weights[1]`,
+/// … in turn, cycling back to the start of `weights`.
+///
+/// Each product is reduced to the sum of its decimal
+/// digits, as Luhn does when doubling (so 7 × 2 = 14
+/// contributes 1 + 4 = 5), and the total must be divisible
+/// by 10. With `weights = &[2, 1]` this is exactly
+/// [luhn_check]. Input requirements and errors are as with
+/// [luhn_check].
+///
+/// # Panics
+///
+/// Panics if `weights` is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::weighted_mod10_check;
+/// assert!(weighted_mod10_check("4263 9826 4026 9299", &[2, 1]).unwrap());
+/// assert!(weighted_mod10_check("1230", &[1, 2, 3]).unwrap());
+/// ```
+pub fn weighted_mod10_check(input: impl AsRef<str>, weights: &[u32]) -> Result<bool, LuhnError> {
+    assert!(!weights.is_empty(), "weights must not be empty");
+    let input = input.as_ref();
+    let (ndigits, _) = luhn_sum(input)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    let mut digits = input.chars().rev().filter_map(|c| c.to_digit(10));
+    let mut total = digits.next().unwrap();
+    for (d, &w) in digits.zip(weights.iter().cycle()) {
+        // A product of a digit and a `u32` weight fits in a
+        // `u64`, and its digit sum is at most 9 * 20.
+        let mut product = u64::from(d) * u64::from(w);
+        while product > 0 {
+            total += (product % 10) as u32;
+            product /= 10;
+        }
+        if total > SUM_LIMIT {
+            total %= 10;
+        }
+    }
+    Ok(total.is_multiple_of(10))
+}
+
+// As with `luhn_sum`, for the Luhn mod N algorithm.
+fn mod_n_sum(
+    input: &str,
+    n: u32,
+    codepoint: impl Fn(char) -> Option<u32>,
+    skip: impl Fn(char) -> bool,
+) -> Result<(usize, [u32; 2]), LuhnError> {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    for (i, c) in input.char_indices() {
+        if skip(c) {
+            continue;
+        }
+        match codepoint(c) {
+            Some(d) if d < n => {
+                let m = valid % 2;
+                sums[1 - m] += d;
+                sums[m] += (2 * d) % n + (2 * d) / n;
+                reduce_sums(&mut sums, n);
+                valid += 1;
+            }
+            _ => return Err(non_digit(i, c)),
+        }
+    }
+    Ok((valid, sums.map(|sum| sum % n)))
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test over a sequence of digit values, as
+/// with [luhn_check]. Each value must be in `0..=9`: any
+/// other value `d` at position `i` is reported as
+/// `BadDigit(i, d)`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_iter;
+/// assert!(luhn_check_iter([1, 5, 8]).unwrap());
+/// assert!(luhn_check_iter("7518".bytes().map(|b| (b - b'0') as u32)).unwrap());
+/// ```
+pub fn luhn_check_iter(digits: impl IntoIterator<Item = u32>) -> Result<bool, LuhnError> {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    for (i, d) in digits.into_iter().enumerate() {
+        if d > 9 {
+            return Err(LuhnError::BadDigit(i, d));
+        }
+        let m = valid % 2;
+        sums[1 - m] += d;
+        sums[m] += DOUBLED[d as usize];
+        reduce_sums(&mut sums, 10);
+        valid += 1;
+    }
+    check_sums(valid, sums)
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check_iter], for a
+/// fixed-length array of digit values (not ASCII). Needs no
+/// string handling or allocation.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_arr;
+/// assert!(luhn_check_arr([1, 5, 8]).unwrap());
+/// assert!(!luhn_check_arr([1, 5, 7]).unwrap());
+/// ```
+pub fn luhn_check_arr<const N: usize>(digits: [u8; N]) -> Result<bool, LuhnError> {
+    luhn_check_iter(digits.map(u32::from))
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test over the decimal digits of `n`. Leading
+/// zeros cannot be represented, which does not change the
+/// result. A number below 10 has only one digit, and so is
+/// never valid; compare `Short` from [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_u64;
+/// assert!(luhn_check_u64(4263982640269299));
+/// assert!(!luhn_check_u64(4263982640269290));
+/// ```
+pub fn luhn_check_u64(n: u64) -> bool {
+    luhn_check_u128(n as u128)
+}
+
+/// As with [luhn_check_u64], for a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_u128;
+/// assert!(luhn_check_u128(79927398713));
+/// ```
+pub fn luhn_check_u128(mut n: u128) -> bool {
+    if n < 10 {
+        return false;
+    }
+    // Work from the right, where the check digit is: every
+    // second digit after it is doubled.
+    let mut sum = 0;
+    let mut double = false;
+    while n > 0 {
+        let d = (n % 10) as u32;
+        sum += if double { DOUBLED[d as usize] } else { d };
+        double = !double;
+        n /= 10;
+    }
+    sum % 10 == 0
+}
+
+/// Incremental [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// state, for input that arrives a `char` at a time. Pushing
+/// the `char`s of a string and then asking for the result
+/// is the same as calling [luhn_check] or [luhn_digit] on
+/// the whole string.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::LuhnState;
+/// let mut state = LuhnState::new();
+/// for c in "75 1".chars() {
+///     state.push(c).unwrap();
+/// }
+/// assert!(!state.is_valid().unwrap());
+/// assert_eq!('8', state.check_digit().unwrap());
+/// state.push('8').unwrap();
+/// assert!(state.is_valid().unwrap());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LuhnState {
+    position: usize,
+    ndigits: usize,
+    sums: [u32; 2],
+}
+
+impl LuhnState {
+    /// A state with no input yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept the next `char` of input. Spaces are skipped.
+    /// Any other non-digit is reported as with [luhn_check],
+    /// with its byte offset in the input so far, and is
+    /// otherwise ignored.
+    pub fn push(&mut self, c: char) -> Result<(), LuhnError> {
+        let i = self.position;
+        self.position += c.len_utf8();
+        if c == ' ' {
+            return Ok(());
+        }
+        if let Some(d) = c.to_digit(10) {
+            let m = self.ndigits % 2;
+            self.sums[1 - m] += d;
+            self.sums[m] += DOUBLED[d as usize];
+            reduce_sums(&mut self.sums, 10);
+            self.ndigits += 1;
+            return Ok(());
+        }
+        Err(non_digit(i, c))
+    }
+
+    /// Whether the input so far passes the check digit
+    /// test, as with [luhn_check].
+    pub fn is_valid(&self) -> Result<bool, LuhnError> {
+        check_sums(self.ndigits, self.sums)
+    }
+
+    /// The check digit for the input so far, as with
+    /// [luhn_digit].
+    pub fn check_digit(&self) -> Result<char, LuhnError> {
+        let digit = digit_sums(self.ndigits, self.sums)?;
+        Ok(char::from_digit(digit as u32, 10).unwrap())
+    }
+}
+
+/// Implementation of the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, as with [luhn_check], for a number
+/// that arrives in fragments, such as fixed-width fields.
+/// The parts are checked as if concatenated, without
+/// joining them: spaces are skipped across part boundaries,
+/// and `NonDigit` positions are byte offsets in the
+/// concatenation, as with [LuhnState].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_parts, LuhnError};
+/// assert!(luhn_check_parts(&["4263 9826", " 4026 9299"]).unwrap());
+/// assert_eq!(Err(LuhnError::NonDigit(4, 'x')), luhn_check_parts(&["15", "8 x"]));
+/// ```
+pub fn luhn_check_parts(parts: &[&str]) -> Result<bool, LuhnError> {
+    let mut state = LuhnState::new();
+    for c in parts.iter().flat_map(|part| part.chars()) {
+        state.push(c)?;
+    }
+    state.is_valid()
+}
+
+/// Payment card brand, as classified by [card_brand].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    Maestro,
+    /// A valid number not matching any of the others.
+    Other,
+}
+
+/// Classify a payment card number by brand. The number is
+/// first checked with [luhn_check]: malformed input is an
+/// error as usual, and a number that fails the check gives
+/// `None`.
+///
+/// Brands are recognized by issuer identification number
+/// (IIN) prefix and by number of digits, following the
+/// [IIN
+/// table](https://en.wikipedia.org/wiki/Payment_card_number#Issuer_identification_number_(IIN))
+/// on Wikipedia:
+///
+/// | Brand      | Prefixes                         | Digits     |
+/// |------------|----------------------------------|------------|
+/// | Visa       | 4                                | 13, 16, 19 |
+/// | Mastercard | 51–55, 2221–2720                 | 16         |
+/// | Amex       | 34, 37                           | 15         |
+/// | Discover   | 6011, 622126–622925, 644–649, 65 | 16–19      |
+/// | Maestro    | 50, 56–69                        | 12–19      |
+///
+/// Brands are tried in the order of the table, so a number
+/// matching both Discover and Maestro is Discover. Anything
+/// else is `Other`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{card_brand, CardBrand};
+/// assert_eq!(Some(CardBrand::Visa), card_brand("4263 9826 4026 9299").unwrap());
+/// assert_eq!(Some(CardBrand::Amex), card_brand("3782 822463 10005").unwrap());
+/// assert_eq!(None, card_brand("4263 9826 4026 9290").unwrap());
+/// ```
+pub fn card_brand(cc_number: impl AsRef<str>) -> Result<Option<CardBrand>, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if !luhn_check(cc_number)? {
+        return Ok(None);
+    }
+    let digits = cc_number.chars().filter_map(|c| c.to_digit(10));
+    Ok(Some(brand_of(digits)))
+}
+
+// Brand of a Luhn-valid number by IIN and length, given its
+// digits.
+fn brand_of(digits: impl Iterator<Item = u32>) -> CardBrand {
+    // Collect up to six leading digits as a number.
+    let mut ndigits = 0;
+    let mut lead = 0;
+    let mut lead_len = 0;
+    for d in digits {
+        if lead_len < 6 {
+            lead = 10 * lead + d;
+            lead_len += 1;
+        }
+        ndigits += 1;
+    }
+    let prefix = |k: u32| lead / 10u32.pow(lead_len - k.min(lead_len));
+
+    let visa = prefix(1) == 4;
+    let mastercard = (51..=55).contains(&prefix(2)) || (2221..=2720).contains(&prefix(4));
+    let amex = [34, 37].contains(&prefix(2));
+    let discover = prefix(2) == 65
+        || (644..=649).contains(&prefix(3))
+        || prefix(4) == 6011
+        || (622126..=622925).contains(&prefix(6));
+    let maestro = prefix(2) == 50 || (56..=69).contains(&prefix(2));
+
+    if visa && [13, 16, 19].contains(&ndigits) {
+        CardBrand::Visa
+    } else if mastercard && ndigits == 16 {
+        CardBrand::Mastercard
+    } else if amex && ndigits == 15 {
+        CardBrand::Amex
+    } else if discover && (16..=19).contains(&ndigits) {
+        CardBrand::Discover
+    } else if maestro && (12..=19).contains(&ndigits) {
+        CardBrand::Maestro
+    } else {
+        CardBrand::Other
+    }
+}
+
+/// The issuer identification number (IIN, or BIN) of a
+/// card number: its first `len` digits, with spaces
+/// stripped. Returns `None` if the number fails [luhn_check],
+/// whose errors are reported as usual. If `len` is more
+/// than the number of digits, all of them are returned.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::card_iin;
+/// assert_eq!(Some(String::from("426398")), card_iin("4263 9826 4026 9299", 6).unwrap());
+/// assert_eq!(None, card_iin("4223 9826 4026 9299", 6).unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn card_iin(cc_number: impl AsRef<str>, len: usize) -> Result<Option<String>, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    if !luhn_check(cc_number)? {
+        return Ok(None);
+    }
+    let iin = cc_number.chars().filter(|&c| c != ' ').take(len).collect();
+    Ok(Some(iin))
+}
+
+/// Everything [inspect_card] finds out about a card number.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardInfo {
+    /// Whether the number passes [luhn_check].
+    pub valid: bool,
+    /// The brand, as with [card_brand]: `None` if the number
+    /// is not valid.
+    pub brand: Option<CardBrand>,
+    /// The digits, with spaces stripped.
+    pub normalized: String,
+    /// The number of digits.
+    pub length: usize,
+}
+
+/// Check `cc_number` with [luhn_check], classify it with
+/// [card_brand] and strip its spaces, all at once. Errors
+/// are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{inspect_card, CardBrand};
+/// let info = inspect_card("4263 9826 4026 9299").unwrap();
+/// assert!(info.valid);
+/// assert_eq!(Some(CardBrand::Visa), info.brand);
+/// assert_eq!("4263982640269299", info.normalized);
+/// assert_eq!(16, info.length);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn inspect_card(cc_number: impl AsRef<str>) -> Result<CardInfo, LuhnError> {
+    let (normalized, valid) = luhn_normalize(cc_number)?;
+    let digits = normalized.chars().filter_map(|c| c.to_digit(10));
+    let brand = valid.then(|| brand_of(digits));
+    Ok(CardInfo {
+        valid,
+        brand,
+        length: normalized.len(),
+        normalized,
+    })
+}
+
+/// Find the numbers in free `text` that pass [luhn_check],
+/// for example to redact card numbers in logs. Returns the
+/// byte offset of each number in `text` together with its
+/// digits, spaces stripped.
+///
+/// A candidate is a maximal run of ASCII digits and spaces,
+/// starting and ending with a digit, so candidates never
+/// overlap: numbers with only spaces between them are taken
+/// together as one. Runs with fewer than `min_len` digits
+/// are skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::find_luhn_numbers;
+/// let text = "paid 25 with 4263 9826 4026 9299, thanks";
+/// assert_eq!(
+///     vec![(13, String::from("4263982640269299"))],
+///     find_luhn_numbers(text, 12),
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn find_luhn_numbers(text: &str, min_len: usize) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+    let mut run: Option<(usize, String)> = None;
+    let mut finish = |run: &mut Option<(usize, String)>| {
+        if let Some((start, digits)) = run.take() {
+            if digits.len() >= min_len && luhn_check(&digits) == Ok(true) {
+                found.push((start, digits));
+            }
+        }
+    };
+    for (i, c) in text.char_indices() {
+        if c.is_ascii_digit() {
+            run.get_or_insert_with(|| (i, String::new())).1.push(c);
+        } else if c != ' ' {
+            finish(&mut run);
+        }
+    }
+    finish(&mut run);
+    found
+}
+
+// As with `luhn_sum`, but with GS1 3-1 weighting in place
+// of Luhn doubling: `sums[0]` weights the even positions
+// by 3, `sums[1]` the odd positions.
+fn gs1_sum(code: &str) -> Result<(usize, [u32; 2]), LuhnError> {
+    let mut valid = 0;
+    let mut sums = [0; 2];
+    for (i, c) in code.char_indices() {
+        if c == ' ' {
+            continue;
+        }
+        if let Some(d) = c.to_digit(10) {
+            let m = valid % 2;
+            sums[1 - m] += d;
+            sums[m] += 3 * d;
+            reduce_sums(&mut sums, 10);
+            valid += 1;
+            continue;
+        }
+        return Err(non_digit(i, c));
+    }
+    Ok((valid, sums.map(|sum| sum % 10)))
+}
+
+/// Check a [GS1](https://www.gs1.org/services/how-calculate-check-digit-manually)
+/// code such as an EAN-13, UPC or GTIN. These use a mod-10
+/// check digit like Luhn, but weight the digits 3, 1, 3, 1…
+/// starting from the digit just left of the check digit,
+/// instead of doubling every second digit. Input
+/// requirements and errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::gs1_check;
+/// assert!(gs1_check("4006381333931").unwrap());
+/// assert!(gs1_check("0 36000 29145 2").unwrap());
+/// assert!(!gs1_check("4006381333932").unwrap());
+/// ```
+pub fn gs1_check(code: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let code = code.as_ref();
+    let (ndigits, sums) = gs1_sum(code)?;
+    check_sums(ndigits, sums)
+}
+
+/// Compute the [GS1](https://www.gs1.org/services/how-calculate-check-digit-manually)
+/// check digit for the given code body, as described for
+/// [gs1_check]. Input requirements and errors are as with
+/// [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::gs1_digit;
+/// assert_eq!('1', gs1_digit("400638133393").unwrap());
+/// ```
+pub fn gs1_digit(code: impl AsRef<str>) -> Result<char, LuhnError> {
+    let code = code.as_ref();
+    let (ndigits, sums) = gs1_sum(code)?;
+    let digit = digit_sums(ndigits, sums)?;
+    Ok(char::from_digit(digit as u32, 10).unwrap())
+}
+
+/// Check a [GS1](https://www.gs1.org/services/how-calculate-check-digit-manually)
+/// code as with [gs1_check], requiring exactly `expected`
+/// digits: 8 for EAN-8, 12 for UPC-A, 13 for EAN-13 and so
+/// on. The weights always start from the check digit, so
+/// codes of any length are handled. Any other number of
+/// digits is reported as `WrongLength`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::gs1_check_len;
+/// assert!(gs1_check_len("96385074", 8).unwrap());
+/// assert!(gs1_check_len("036000291452", 12).unwrap());
+/// ```
+pub fn gs1_check_len(code: impl AsRef<str>, expected: usize) -> Result<bool, LuhnError> {
+    let code = code.as_ref();
+    let (ndigits, sums) = gs1_sum(code)?;
+    if ndigits != expected {
+        return Err(LuhnError::WrongLength {
+            expected,
+            actual: ndigits,
+        });
+    }
+    check_sums(ndigits, sums)
+}
+
+/// Operation table of the weakly totally anti-symmetric
+/// quasigroup used by [damm_check].
+const DAMM: [[u8; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+fn damm_interim(input: &str) -> Result<(usize, u8), LuhnError> {
+    let mut valid = 0;
+    let mut interim = 0;
+    for (i, c) in input.char_indices() {
+        if c == ' ' {
+            continue;
+        }
+        if let Some(d) = c.to_digit(10) {
+            interim = DAMM[interim as usize][d as usize];
+            valid += 1;
+            continue;
+        }
+        return Err(non_digit(i, c));
+    }
+    Ok((valid, interim))
+}
+
+/// Check a number against the [Damm
+/// algorithm](https://en.wikipedia.org/wiki/Damm_algorithm)
+/// check digit. Unlike Luhn, Damm detects every
+/// single-digit error and every adjacent transposition.
+/// Input requirements and errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::damm_check;
+/// assert!(damm_check("5724").unwrap());
+/// assert!(!damm_check("5742").unwrap());
+/// ```
+pub fn damm_check(input: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let input = input.as_ref();
+    let (ndigits, interim) = damm_interim(input)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    Ok(interim == 0)
+}
+
+/// Compute the [Damm
+/// algorithm](https://en.wikipedia.org/wiki/Damm_algorithm)
+/// check digit for the given number body, as described for
+/// [damm_check]. Input requirements and errors are as with
+/// [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::damm_digit;
+/// assert_eq!('4', damm_digit("572").unwrap());
+/// ```
+pub fn damm_digit(input: impl AsRef<str>) -> Result<char, LuhnError> {
+    let input = input.as_ref();
+    let (ndigits, interim) = damm_interim(input)?;
+    if ndigits == 0 {
+        return Err(LuhnError::Empty);
+    }
+    Ok(char::from_digit(interim as u32, 10).unwrap())
+}
+
+/// Multiplication table of the dihedral group D5 used by
+/// [verhoeff_check].
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+/// Permutation applied to a digit according to its position
+/// from the right, modulo 8.
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 0, 3, 9, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+/// Inverses in D5.
+const VERHOEFF_INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+/// Verhoeff checksum of `input`, with the rightmost digit
+/// taken to be at position `offset` from the right.
+fn verhoeff_sum(input: &str, offset: usize) -> Result<(usize, u8), LuhnError> {
+    // Validate first, then fold from the right: D5 is not
+    // commutative, so the order matters.
+    let (ndigits, _) = luhn_sum(input)?;
+    let mut c = 0;
+    let digits = input.chars().rev().filter_map(|c| c.to_digit(10));
+    for (i, d) in digits.enumerate() {
+        let p = VERHOEFF_P[(i + offset) % 8][d as usize];
+        c = VERHOEFF_D[c as usize][p as usize];
+    }
+    Ok((ndigits, c))
+}
+
+/// Check a number against the [Verhoeff
+/// algorithm](https://en.wikipedia.org/wiki/Verhoeff_algorithm)
+/// check digit. Verhoeff detects every single-digit error
+/// and every adjacent transposition, including the `09`/`90`
+/// swap that Luhn misses. Input requirements and errors are
+/// as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::verhoeff_check;
+/// assert!(verhoeff_check("2363").unwrap());
+/// assert!(!verhoeff_check("2336").unwrap());
+/// ```
+pub fn verhoeff_check(input: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let input = input.as_ref();
+    let (ndigits, c) = verhoeff_sum(input, 0)?;
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+    Ok(c == 0)
+}
+
+/// Compute the [Verhoeff
+/// algorithm](https://en.wikipedia.org/wiki/Verhoeff_algorithm)
+/// check digit for the given number body, as described for
+/// [verhoeff_check]. Input requirements and errors are as
+/// with [luhn_digit].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::verhoeff_digit;
+/// assert_eq!('3', verhoeff_digit("236").unwrap());
+/// ```
+pub fn verhoeff_digit(input: impl AsRef<str>) -> Result<char, LuhnError> {
+    let input = input.as_ref();
+    let (ndigits, c) = verhoeff_sum(input, 1)?;
+    if ndigits == 0 {
+        return Err(LuhnError::Empty);
+    }
+    let digit = VERHOEFF_INV[c as usize];
+    Ok(char::from_digit(digit as u32, 10).unwrap())
+}
+
+/// Check an Indian [Aadhaar](https://en.wikipedia.org/wiki/Aadhaar)
+/// number. An Aadhaar number is exactly 12 digits with a
+/// [Verhoeff](https://en.wikipedia.org/wiki/Verhoeff_algorithm)
+/// check digit, as with [verhoeff_check]. Spaces are skipped
+/// as with [luhn_check]. Any other number of digits is
+/// reported as `WrongLength`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::aadhaar_check;
+/// assert!(aadhaar_check("2341 2341 2346").unwrap());
+/// assert!(!aadhaar_check("2341 2341 2345").unwrap());
+/// ```
+pub fn aadhaar_check(id: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let id = id.as_ref();
+    let ndigits = digit_count(id)?;
+    if ndigits != 12 {
+        return Err(LuhnError::WrongLength {
+            expected: 12,
+            actual: ndigits,
+        });
+    }
+    verhoeff_check(id)
+}
+
+/// A check digit scheme, for code generic over the scheme.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{CheckDigit, Gs1, Luhn, LuhnModN};
+/// let schemes: [&dyn CheckDigit; 3] = [&Luhn, &Gs1, &LuhnModN(16)];
+/// for scheme in schemes {
+///     let digit = scheme.digit("123").unwrap();
+///     let complete = format!("123{}", digit);
+///     assert!(scheme.check(&complete).unwrap());
+/// }
+/// ```
+pub trait CheckDigit {
+    /// Whether `input` passes the check digit test.
+    fn check(&self, input: &str) -> Result<bool, LuhnError>;
+    /// The check digit to append to `input`.
+    fn digit(&self, input: &str) -> Result<char, LuhnError>;
+}
+
+/// The [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm):
+/// [luhn_check] and [luhn_digit].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Luhn;
+
+impl CheckDigit for Luhn {
+    fn check(&self, input: &str) -> Result<bool, LuhnError> {
+        luhn_check(input)
+    }
+
+    fn digit(&self, input: &str) -> Result<char, LuhnError> {
+        luhn_digit(input)
+    }
+}
+
+/// GS1 3-1 weighting: [gs1_check] and [gs1_digit].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gs1;
+
+impl CheckDigit for Gs1 {
+    fn check(&self, input: &str) -> Result<bool, LuhnError> {
+        gs1_check(input)
+    }
+
+    fn digit(&self, input: &str) -> Result<char, LuhnError> {
+        gs1_digit(input)
+    }
+}
+
+/// The Luhn mod N algorithm, as with [luhn_check_mod_n],
+/// over the given radix in `2..=36`. Input digits are as
+/// for `char::to_digit`, so letters are case-insensitive;
+/// generated check digits are as for `char::from_digit`,
+/// so letters are lowercase.
+///
+/// # Panics
+///
+/// Panics if the radix is greater than 36.
+#[derive(Debug, Clone, Copy)]
+pub struct LuhnModN(pub u32);
+
+impl CheckDigit for LuhnModN {
+    fn check(&self, input: &str) -> Result<bool, LuhnError> {
+        let n = self.0;
+        luhn_check_mod_n(input, n, |c| c.to_digit(n))
+    }
+
+    fn digit(&self, input: &str) -> Result<char, LuhnError> {
+        let n = self.0;
+        luhn_mod_n_digit(
+            input,
+            n,
+            |c| c.to_digit(n),
+            |d| char::from_digit(d, n).unwrap(),
+        )
+    }
+}
+
+/// Check an [IMEI](https://en.wikipedia.org/wiki/International_Mobile_Equipment_Identity)
+/// device identifier. An IMEI is exactly 15 digits, the
+/// last of which is a Luhn check digit. Spaces are skipped
+/// as with [luhn_check]. Fewer than 15 digits is reported
+/// as `Short`, more as `TooLong`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::imei_check;
+/// assert!(imei_check("490154203237518").unwrap());
+/// assert!(imei_check("49 015420 323751 8").unwrap());
+/// assert!(!imei_check("490154203237519").unwrap());
+/// ```
+pub fn imei_check(imei: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let imei = imei.as_ref();
+    let (ndigits, sums) = luhn_sum(imei)?;
+    match ndigits {
+        15 => check_sums(ndigits, sums),
+        n if n < 15 => Err(too_short(n)),
+        n => Err(LuhnError::TooLong(n)),
+    }
+}
+
+/// Check a hexadecimal [MEID](https://en.wikipedia.org/wiki/Mobile_equipment_identifier)
+/// device identifier together with its check digit. This is
+/// 15 hex digits, the last of which is a check digit by the
+/// Luhn mod N algorithm in base 16, as with
+/// [luhn_check_mod_n]. Hex letters may be either case, and
+/// spaces and dashes are skipped. Any other `char` is
+/// reported as with [luhn_check], and any other number of
+/// hex digits as `WrongLength`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::meid_check_hex;
+/// assert!(meid_check_hex("AF 01 23 45 0A BC DE C").unwrap());
+/// assert!(!meid_check_hex("AF 01 23 45 0A BC DE D").unwrap());
+/// ```
+pub fn meid_check_hex(meid: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let meid = meid.as_ref();
+    let (ndigits, sums) = mod_n_sum(meid, 16, |c| c.to_digit(16), |c| c == ' ' || c == '-')?;
+    if ndigits != 15 {
+        return Err(LuhnError::WrongLength {
+            expected: 15,
+            actual: ndigits,
+        });
+    }
+    Ok(sums[ndigits % 2].is_multiple_of(16))
+}
+
+/// Check a US healthcare [National Provider
+/// Identifier](https://en.wikipedia.org/wiki/National_Provider_Identifier).
+/// An NPI is exactly 10 digits; its check digit is the Luhn
+/// check digit of the NPI with the constant `80840`
+/// prepended. Spaces are skipped as with [luhn_check]. Any
+/// other number of digits is reported as `WrongLength`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::npi_check;
+/// assert!(npi_check("1234567893").unwrap());
+/// assert!(!npi_check("1234567890").unwrap());
+/// ```
+pub fn npi_check(npi: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let npi = npi.as_ref();
+    let (ndigits, _) = luhn_sum(npi)?;
+    if ndigits != 10 {
+        return Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: ndigits,
+        });
+    }
+    let digits = npi.chars().filter_map(|c| c.to_digit(10));
+    let (ndigits, sums) = sum_digits([8, 0, 8, 4, 0].into_iter().chain(digits));
+    check_sums(ndigits, sums)
+}
+
+/// Check a Canadian [Social Insurance
+/// Number](https://en.wikipedia.org/wiki/Social_insurance_number).
+/// A SIN is exactly 9 digits with a Luhn check digit. Spaces
+/// and dashes are skipped. Any other number of digits is
+/// reported as `WrongLength`.
+///
+/// SINs beginning with `0` or `8` are not issued to
+/// individuals (`0` is reserved for fictitious numbers and
+/// `8` for business numbers), so they fail the check even
+/// when the check digit is right.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, sin_check};
+/// assert!(sin_check("130-692-544").unwrap());
+/// assert!(luhn_check("046 454 286").unwrap());
+/// assert!(!sin_check("046 454 286").unwrap());
+/// ```
+pub fn sin_check(sin: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let sin = sin.as_ref();
+    let (ndigits, sums) = luhn_sum_skip(sin, |c| c == ' ' || c == '-')?;
+    if ndigits != 9 {
+        return Err(LuhnError::WrongLength {
+            expected: 9,
+            actual: ndigits,
+        });
+    }
+    let first = sin.chars().find(char::is_ascii_digit).unwrap();
+    if first == '0' || first == '8' {
+        return Ok(false);
+    }
+    check_sums(ndigits, sums)
+}
+
+/// Check an [ISIN](https://en.wikipedia.org/wiki/International_Securities_Identification_Number)
+/// securities identifier. An ISIN is exactly 12 ASCII
+/// alphanumeric characters: a two-letter country code, nine
+/// alphanumeric characters, and a decimal check digit.
+/// Letters are case-insensitive.
+///
+/// Each letter is expanded to its two-digit value (`A` is
+/// 10, …, `Z` is 35), and the resulting digit string is
+/// checked with the Luhn Algorithm. The expansion is done
+/// on the fly, without allocating.
+///
+/// Fewer than 12 characters is reported as `Short`, more as
+/// `TooLong`. A character not allowed at its position is
+/// reported as `NonDigit`, or as `UnexpectedWhitespace` or
+/// `NonAsciiDigit` if it is whitespace or a non-ASCII
+/// digit.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::isin_check;
+/// assert!(isin_check("US0378331005").unwrap());
+/// assert!(!isin_check("US0378331006").unwrap());
+/// ```
+pub fn isin_check(isin: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let isin = isin.as_ref();
+    let len = isin.chars().count();
+    if len < 12 {
+        return Err(too_short(len));
+    }
+    if len > 12 {
+        return Err(LuhnError::TooLong(len));
+    }
+    for (k, (i, c)) in isin.char_indices().enumerate() {
+        let ok = match k {
+            0 | 1 => c.is_ascii_alphabetic(),
+            11 => c.is_ascii_digit(),
+            _ => c.is_ascii_alphanumeric(),
+        };
+        if !ok {
+            return Err(non_digit(i, c));
+        }
+    }
+    let digits = isin.chars().flat_map(|c| {
+        let v = c.to_digit(36).unwrap();
+        if v < 10 {
+            [None, Some(v)]
+        } else {
+            [Some(v / 10), Some(v % 10)]
+        }
+    });
+    let (ndigits, sums) = sum_digits(digits.flatten());
+    check_sums(ndigits, sums)
+}
+
+/// Check an [ISBN-10](https://en.wikipedia.org/wiki/ISBN#ISBN-10_check_digits)
+/// book number. This is not the Luhn Algorithm: the ten
+/// digits are weighted 10, 9, …, 1 from the left, and the
+/// weighted sum must be divisible by 11. The last `char`
+/// may be `X` or `x` standing for 10. Spaces and hyphens
+/// are skipped.
+///
+/// Any other number of digits is reported as
+/// `WrongLength`. A non-digit, or an `X` before the last
+/// position, is reported as `NonDigit`, or as
+/// `UnexpectedWhitespace` or `NonAsciiDigit` if it is
+/// whitespace or a non-ASCII digit.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::isbn10_check;
+/// assert!(isbn10_check("0-306-40615-2").unwrap());
+/// assert!(isbn10_check("097522980X").unwrap());
+/// assert!(!isbn10_check("0306406153").unwrap());
+/// ```
+pub fn isbn10_check(isbn: impl AsRef<str>) -> Result<bool, LuhnError> {
+    let isbn = isbn.as_ref();
+    let mut ndigits = 0;
+    let mut sum = 0;
+    for (i, c) in isbn.char_indices() {
+        if c == ' ' || c == '-' {
+            continue;
+        }
+        let d = match c {
+            'X' | 'x' if ndigits == 9 => 10,
+            _ => c.to_digit(10).ok_or_else(|| non_digit(i, c))?,
+        };
+        sum += 10usize.saturating_sub(ndigits) as u32 * d;
+        ndigits += 1;
+    }
+    if ndigits != 10 {
+        return Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: ndigits,
+        });
+    }
+    Ok(sum.is_multiple_of(11))
+}
+
+/// Replace the check digit of a complete number with the
+/// correct [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit for the rest of the number. Spaces are
+/// stripped, so a number that already passes is returned
+/// as a bare digit string. Errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_repair;
+/// assert_eq!("7518", luhn_repair("751 0").unwrap());
+/// assert_eq!("7518", luhn_repair("7518").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_repair(cc_number: impl AsRef<str>) -> Result<String, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let expected = match luhn_verify(cc_number) {
+        Ok(()) => None,
+        Err(LuhnError::CheckDigit { expected, .. }) => Some(expected),
+        Err(e) => return Err(e),
+    };
+    let mut result: String = cc_number.chars().filter(|&c| c != ' ').collect();
+    if let Some(expected) = expected {
+        result.pop();
+        result.push(expected);
+    }
+    Ok(result)
+}
+
+/// For each prefix of `cc_number` that ends at a digit and
+/// contains at least two digits, whether that prefix passes
+/// [luhn_check]. The last element is the result for the
+/// whole number. This is computed in a single pass, and can
+/// help locate where a mistyped number went wrong. Errors
+/// are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_prefix_states;
+/// assert_eq!(vec![false, true, false], luhn_prefix_states("1 5 8 0").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_prefix_states(cc_number: impl AsRef<str>) -> Result<Vec<bool>, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut state = LuhnState::new();
+    let mut states = Vec::new();
+    for c in cc_number.chars() {
+        state.push(c)?;
+        if c != ' ' && state.ndigits >= 2 {
+            states.push(state.is_valid()?);
+        }
+    }
+    if state.ndigits < 2 {
+        return Err(too_short(state.ndigits));
+    }
+    Ok(states)
+}
+
+/// Check each of `numbers` with [luhn_check], returning the
+/// results in order. A malformed number gives an `Err` in
+/// its place without stopping the rest of the batch.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_all, LuhnError};
+/// assert_eq!(
+///     vec![Ok(true), Ok(false), Err(LuhnError::Short(1))],
+///     luhn_check_all(["158", "157", "1"]),
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_check_all<'a>(
+    numbers: impl IntoIterator<Item = &'a str>,
+) -> Vec<Result<bool, LuhnError>> {
+    numbers.into_iter().map(luhn_check).collect()
+}
+
+/// Check each line read from `reader` with [luhn_check],
+/// returning the results in order, as with
+/// [luhn_check_all]. Lines are read one at a time, so the
+/// whole input is never held in memory. Read errors,
+/// including invalid UTF-8, are returned as the outer
+/// `Err`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_reader, LuhnError};
+/// let input = std::io::Cursor::new("158\n157\n1\n");
+/// assert_eq!(
+///     vec![Ok(true), Ok(false), Err(LuhnError::Short(1))],
+///     luhn_check_reader(input).unwrap(),
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn luhn_check_reader(
+    reader: impl std::io::Read,
+) -> std::io::Result<Vec<Result<bool, LuhnError>>> {
+    use std::io::BufRead;
+
+    std::io::BufReader::new(reader)
+        .lines()
+        .map(|line| Ok(luhn_check(line?)))
+        .collect()
+}
+
+/// Complete each line read from `reader` as a number body:
+/// compute its check digit with [luhn_digit] and write the
+/// line followed by the digit and a newline to `writer`. A
+/// line that [luhn_digit] rejects, such as an empty line or
+/// one with a non-digit, is written through unchanged, so
+/// the output has one line for each input line. Read and
+/// write errors, including invalid UTF-8, are returned.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::complete_stream;
+/// let input = std::io::Cursor::new("15\n751\nfoo\n");
+/// let mut output = Vec::new();
+/// complete_stream(input, &mut output).unwrap();
+/// assert_eq!(b"158\n7518\nfoo\n", output.as_slice());
+/// ```
+#[cfg(feature = "std")]
+pub fn complete_stream(
+    reader: impl std::io::BufRead,
+    writer: &mut impl std::io::Write,
+) -> std::io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        match luhn_digit(&line) {
+            Ok(d) => writeln!(writer, "{}{}", line, d)?,
+            Err(_) => writeln!(writer, "{}", line)?,
+        }
+    }
+    Ok(())
+}
+
+/// As with [luhn_check_all], but checks the numbers in
+/// parallel. The results are in the same order as
+/// `numbers`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_par;
+/// assert_eq!(vec![Ok(true), Ok(false)], luhn_check_par(&["158", "157"]));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn luhn_check_par(numbers: &[&str]) -> Vec<Result<bool, LuhnError>> {
+    use rayon::prelude::*;
+
+    numbers.par_iter().map(luhn_check).collect()
+}
+
+/// Whether every one of `numbers` is well-formed and passes
+/// [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_all_ok;
+/// assert!(luhn_check_all_ok(["158", "7518"]));
+/// assert!(!luhn_check_all_ok(["158", "1"]));
+/// ```
+pub fn luhn_check_all_ok<'a>(numbers: impl IntoIterator<Item = &'a str>) -> bool {
+    numbers.into_iter().all(|n| luhn_check(n) == Ok(true))
+}
+
+// Known answers for [luhn_check], shared by [luhn_self_test]
+// and the unit tests.
+const SELF_TEST_CHECKS: [(&str, Result<bool, LuhnError>); 10] = [
+    ("158", Ok(true)),
+    ("513", Ok(true)),
+    ("7518", Ok(true)),
+    ("4263 9826 4026 9299", Ok(true)),
+    ("4223 9826 4026 9299", Ok(false)),
+    ("7992 7398 713", Ok(true)),
+    ("490154203237518", Ok(true)),
+    ("0 foo", Err(LuhnError::NonDigit(2, 'f'))),
+    ("1", Err(LuhnError::Short(1))),
+    (" ", Err(LuhnError::Empty)),
+];
+
+// Known answers for [luhn_digit], as for `SELF_TEST_CHECKS`.
+const SELF_TEST_DIGITS: [(&str, Result<char, LuhnError>); 5] = [
+    ("15", Ok('8')),
+    ("751", Ok('8')),
+    ("4263 9826 4026 929", Ok('9')),
+    ("7992 7398 71", Ok('3')),
+    ("0 foo", Err(LuhnError::NonDigit(2, 'f'))),
+];
+
+/// Run a fixed suite of known answers through [luhn_check],
+/// [luhn_digit] and [imei_check], including error cases,
+/// returning whether all of them come out as expected. This
+/// is for checking the crate at run time where `cargo test`
+/// is not available; the unit tests use the same vectors.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_self_test;
+/// assert!(luhn_self_test());
+/// ```
+pub fn luhn_self_test() -> bool {
+    SELF_TEST_CHECKS.iter().all(|(s, r)| luhn_check(s) == *r)
+        && SELF_TEST_DIGITS.iter().all(|(s, r)| luhn_digit(s) == *r)
+        && imei_check("490154203237518") == Ok(true)
+        && imei_check("490154203237519") == Ok(false)
+}
+
+/// Check each line of `buf` with [luhn_check], yielding the
+/// results lazily in order, as with `luhn_check_reader` but
+/// over a buffer already in memory. Lines end with `\n` or
+/// `\r\n`, and a final line ending is optional. Each line
+/// gets a result, so an empty line yields `Err(Empty)`.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_lines, LuhnError};
+/// let results: Vec<_> = luhn_check_lines("158\r\n157\n\n").collect();
+/// assert_eq!(vec![Ok(true), Ok(false), Err(LuhnError::Empty)], results);
+/// ```
+pub fn luhn_check_lines(buf: &str) -> impl Iterator<Item = Result<bool, LuhnError>> + '_ {
+    buf.lines().map(luhn_check)
+}
+
+/// Append the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit to `s` in place. Spaces in `s` are skipped
+/// when computing the check digit and are left as they
+/// are; the digit is pushed directly onto the end. Errors
+/// are as with [luhn_digit], and leave `s` unchanged.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::append_check_digit;
+/// let mut s = String::from("7 51");
+/// append_check_digit(&mut s).unwrap();
+/// assert_eq!("7 518", s);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn append_check_digit(s: &mut String) -> Result<(), LuhnError> {
+    let digit = luhn_digit(s.as_str())?;
+    s.push(digit);
+    Ok(())
+}
+
+/// Regroup `digits` for display: existing spaces are
+/// stripped, and the remaining `char`s are split into
+/// groups of `group` separated by `sep`. A short final
+/// group is left as it is. If `group` is 0, the stripped
+/// `char`s are returned without grouping. The input is not
+/// checked.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::format_grouped;
+/// assert_eq!("4263 9826 4026 9299", format_grouped("4263982640269299", 4, ' '));
+/// assert_eq!("7992-7398-713", format_grouped("7992 73 98713", 4, '-'));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn format_grouped(digits: impl AsRef<str>, group: usize, sep: char) -> String {
+    let digits = digits.as_ref();
+    let mut result = String::with_capacity(digits.len() + digits.len() / group.max(1));
+    for (i, c) in digits.chars().filter(|&c| c != ' ').enumerate() {
+        if group > 0 && i > 0 && i % group == 0 {
+            result.push(sep);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Make a token from the digits of `body`: append its check
+/// digit, and group the result in fours joined by hyphens,
+/// as `1234-5678-9012-3452`. Hyphens and spaces in `body`
+/// are skipped; errors are as with [luhn_digit]. The result
+/// passes [luhn_check_token].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check_token, luhn_token};
+/// let token = luhn_token("4263-9826-4026-929").unwrap();
+/// assert_eq!("4263-9826-4026-9299", token);
+/// assert!(luhn_check_token(&token).unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_token(body: impl AsRef<str>) -> Result<String, LuhnError> {
+    let body = body.as_ref();
+    let (ndigits, sums) = luhn_sum_skip(body, |c| c == ' ' || c == '-')?;
+    let digit = digit_sums(ndigits, sums)?;
+    let mut digits = String::with_capacity(ndigits + 1);
+    digits.extend(body.chars().filter(char::is_ascii_digit));
+    digits.push(char::from(b'0' + digit));
+    Ok(format_grouped(digits, 4, '-'))
+}
+
+/// Mask `cc_number` for display, replacing all but the last
+/// `visible_tail` digits with `mask_char`. Spaces are kept
+/// where they are. If `visible_tail` is at least the number
+/// of digits, nothing is masked. The input must be as for
+/// [luhn_check], but the check digit is not tested.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::mask;
+/// assert_eq!(
+///     "**** **** **** 9299",
+///     mask("4263 9826 4026 9299", 4, '*').unwrap(),
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn mask(
+    cc_number: impl AsRef<str>,
+    visible_tail: usize,
+    mask_char: char,
+) -> Result<String, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut hidden = digit_count(cc_number)?.saturating_sub(visible_tail);
+    let masked = cc_number
+        .chars()
+        .map(|c| {
+            if c == ' ' || hidden == 0 {
+                return c;
+            }
+            hidden -= 1;
+            mask_char
+        })
+        .collect();
+    Ok(masked)
+}
+
+/// Strip the spaces from `cc_number` and check it with
+/// [luhn_check] in a single pass, returning the digits
+/// together with whether they pass. Errors are as with
+/// [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_normalize;
+/// assert_eq!(
+///     (String::from("7518"), true),
+///     luhn_normalize(" 75 18").unwrap(),
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_normalize(cc_number: impl AsRef<str>) -> Result<(String, bool), LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let mut digits = String::with_capacity(cc_number.len());
+    let mut state = LuhnState::new();
+    for c in cc_number.chars() {
+        state.push(c)?;
+        if c != ' ' {
+            digits.push(c);
+        }
+    }
+    let valid = state.is_valid()?;
+    Ok((digits, valid))
+}
+
+/// As with [luhn_normalize], but a number that fails the
+/// check is an error: strip the spaces from `cc_number` and
+/// check it in a single pass, returning the digits. The
+/// result is the only allocation, made up front with
+/// capacity `cc_number.len()`.
+///
+/// A wrong check digit is reported as `CheckFailed`. That
+/// error has no room for the digits; use [luhn_normalize]
+/// to get them either way. Other errors are as with
+/// [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_clean, LuhnError};
+/// assert_eq!(Ok(String::from("7518")), luhn_clean(" 75 18"));
+/// assert_eq!(Err(LuhnError::CheckFailed), luhn_clean(" 75 19"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_clean(cc_number: impl AsRef<str>) -> Result<String, LuhnError> {
+    let (digits, valid) = luhn_normalize(cc_number)?;
+    if !valid {
+        return Err(LuhnError::CheckFailed);
+    }
+    Ok(digits)
+}
+
+/// Check `cc_number` with [luhn_check] and split it into
+/// its body, with spaces stripped, and its check digit. A
+/// wrong check digit is reported as `CheckFailed`; other
+/// errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_split, LuhnError};
+/// assert_eq!(Ok((String::from("751"), '8')), luhn_split("75 18"));
+/// assert_eq!(Err(LuhnError::CheckFailed), luhn_split("7519"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_split(cc_number: impl AsRef<str>) -> Result<(String, char), LuhnError> {
+    let (mut body, valid) = luhn_normalize(cc_number)?;
+    if !valid {
+        return Err(LuhnError::CheckFailed);
+    }
+    // luhn_normalize has checked that there are at least
+    // two digits.
+    let check = body.pop().unwrap();
+    Ok((body, check))
+}
+
+/// Suggest corrections for a number that fails the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test: every number that differs from it in a
+/// single digit and passes, spaces stripped, in order of
+/// the changed position. A number that already passes is
+/// returned as the only suggestion.
+///
+/// Since doubling permutes the digits, each position has
+/// exactly one replacement that fixes the sum, so there are
+/// always as many suggestions as digits. Each is found from
+/// the sums directly rather than by re-checking. Input
+/// requirements and errors are as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_suggest;
+/// assert_eq!(vec!["158"], luhn_suggest("1 58").unwrap());
+/// assert_eq!(vec!["257", "117", "158"], luhn_suggest("157").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_suggest(cc_number: impl AsRef<str>) -> Result<Vec<String>, LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let (digits, valid) = luhn_normalize(cc_number)?;
+    if valid {
+        return Ok(vec![digits]);
+    }
+    let ndigits = digits.len();
+    let (_, sums) = luhn_sum(&digits)?;
+    let total = sums[ndigits % 2];
+    let mut suggestions = Vec::with_capacity(ndigits);
+    for (k, c) in digits.char_indices() {
+        let d = c.to_digit(10).unwrap();
+        let doubled = k % 2 == ndigits % 2;
+        let weigh = |d: u32| if doubled { DOUBLED[d as usize] } else { d };
+        let rest = total + 10 - weigh(d);
+        let e = (0..10)
+            .find(|&e| (rest + weigh(e)).is_multiple_of(10))
+            .unwrap();
+        let mut fixed = String::with_capacity(ndigits);
+        fixed.push_str(&digits[..k]);
+        fixed.push(char::from_digit(e, 10).unwrap());
+        fixed.push_str(&digits[k + 1..]);
+        suggestions.push(fixed);
+    }
+    Ok(suggestions)
+}
+
+/// Largest number of free digits `luhn_completions` will
+/// enumerate.
+pub const MAX_COMPLETION_FREE_DIGITS: usize = 6;
+
+/// Every number of `total_len` digits that starts with the
+/// digits of `prefix` and passes the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test, in increasing order. Spaces in
+/// `prefix` are skipped as with [luhn_check].
+///
+/// Each choice of the free digits between the prefix and
+/// the check digit gives exactly one completion, so there
+/// are `10^free` results: this grows exponentially.
+///
+/// A `total_len` below 2 is reported as `Short`, or as
+/// `Empty` if it is 0. A prefix that leaves no room for the
+/// check digit is reported as `TooLong` with the prefix's
+/// number of digits, since it is the prefix that is too
+/// long: it may have at most `total_len - 1` digits. More
+/// than [MAX_COMPLETION_FREE_DIGITS] free digits is reported
+/// as `TooLong` with `total_len`. Errors in `prefix` are as
+/// with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_completions;
+/// let all = luhn_completions("1", 3).unwrap();
+/// assert_eq!(10, all.len());
+/// assert_eq!(["109", "117", "125"], all[..3]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_completions(
+    prefix: impl AsRef<str>,
+    total_len: usize,
+) -> Result<Vec<String>, LuhnError> {
+    let prefix = prefix.as_ref();
+    if total_len < 2 {
+        return Err(too_short(total_len));
+    }
+    let (ndigits, _) = luhn_sum(prefix)?;
+    if ndigits > total_len - 1 {
+        return Err(LuhnError::TooLong(ndigits));
+    }
+    let free = total_len - 1 - ndigits;
+    if free > MAX_COMPLETION_FREE_DIGITS {
+        return Err(LuhnError::TooLong(total_len));
+    }
+
+    let mut body = String::with_capacity(total_len);
+    body.extend(prefix.chars().filter(|&c| c != ' '));
+    let count = 10usize.pow(free as u32);
+    let mut completions = Vec::with_capacity(count);
+    let mut filler = [b'0'; MAX_COMPLETION_FREE_DIGITS];
+    for mut k in 0..count {
+        for b in filler[..free].iter_mut().rev() {
+            *b = b'0' + (k % 10) as u8;
+            k /= 10;
+        }
+        let mut number = body.clone();
+        number.extend(filler[..free].iter().map(|&b| char::from(b)));
+        append_check_digit(&mut number)?;
+        completions.push(number);
+    }
+    Ok(completions)
+}
+
+/// Check `cc_number` with [luhn_check], or fill in its check
+/// digit if it is a placeholder. If the last non-space
+/// `char` is `*`, it is replaced with the check digit from
+/// [luhn_digit] and the filled-in number is returned with
+/// `true`. Otherwise the number is returned as it is,
+/// together with the result of [luhn_check]. A `*`
+/// anywhere else is reported as `NonDigit`, and any other
+/// bad input as with [luhn_check].
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_check_or_fill;
+/// assert_eq!((String::from("158"), true), luhn_check_or_fill("15*").unwrap());
+/// assert_eq!((String::from("157"), false), luhn_check_or_fill("157").unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_check_or_fill(cc_number: impl AsRef<str>) -> Result<(String, bool), LuhnError> {
+    let cc_number = cc_number.as_ref();
+    let trimmed = cc_number.trim_end_matches(' ');
+    if let Some(body) = trimmed.strip_suffix('*') {
+        let digit = luhn_digit(body)?;
+        let mut filled = String::with_capacity(cc_number.len());
+        filled.push_str(body);
+        filled.push(digit);
+        filled.push_str(&cc_number[trimmed.len()..]);
+        return Ok((filled, true));
+    }
+    let valid = luhn_check(cc_number)?;
+    Ok((String::from(cc_number), valid))
+}
+
+/// A number that is known to pass the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. The only way to build one is by
+/// parsing, which runs [luhn_check]: a failed check is
+/// reported as `CheckFailed`.
+///
+/// The number is stored as given, including any spaces.
+/// Equality and hashing ignore the spaces, so numbers that
+/// differ only in formatting are equal.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{LuhnError, LuhnNumber};
+/// let n: LuhnNumber = "4263 9826 4026 9299".parse().unwrap();
+/// assert_eq!("4263 9826 4026 9299", n.digits());
+/// assert!(matches!("157".parse::<LuhnNumber>(), Err(LuhnError::CheckFailed)));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct LuhnNumber(String);
+
+#[cfg(feature = "alloc")]
+impl LuhnNumber {
+    /// The stored number.
+    pub fn digits(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl LuhnNumber {
+    fn digit_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.bytes().filter(|&b| b != b' ')
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq for LuhnNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.digit_bytes().eq(other.digit_bytes())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Eq for LuhnNumber {}
+
+#[cfg(feature = "alloc")]
+impl core::hash::Hash for LuhnNumber {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        for b in self.digit_bytes() {
+            state.write_u8(b);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for LuhnNumber {
+    type Err = LuhnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !luhn_check(s)? {
+            return Err(LuhnError::CheckFailed);
+        }
+        Ok(LuhnNumber(s.into()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&str> for LuhnNumber {
+    type Error = LuhnError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Takes ownership of the string on success rather than
+/// copying it.
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for LuhnNumber {
+    type Error = LuhnError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !luhn_check(&s)? {
+            return Err(LuhnError::CheckFailed);
+        }
+        Ok(LuhnNumber(s))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for LuhnNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Find the digits that can replace the single `wildcard`
+/// in `masked` to make a number that passes the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. Returns each solution as the position
+/// of the wildcard (as for `NonDigit`) and the replacement
+/// digit. The input is otherwise as for [luhn_check]; any
+/// count of wildcards other than one is reported as
+/// `Wildcards`.
+///
+/// The solution is computed directly from the sums of the
+/// known digits rather than by trying each digit. For the
+/// standard algorithm there is always exactly one solution,
+/// since doubling is a permutation of the digits.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::luhn_solve_wildcard;
+/// assert_eq!(vec![(1, '5')], luhn_solve_wildcard("1*8", '*').unwrap());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn luhn_solve_wildcard(
+    masked: impl AsRef<str>,
+    wildcard: char,
+) -> Result<Vec<(usize, char)>, LuhnError> {
+    let masked = masked.as_ref();
+    let mut wildcards = 0;
+    let mut unknown = (0, 0);
+    let mut ndigits = 0;
+    let mut sums = [0; 2];
+    for (i, c) in masked.char_indices() {
+        if c == wildcard {
+            // Contributes zero to both sums until solved.
+            wildcards += 1;
+            unknown = (i, ndigits);
+            ndigits += 1;
+            continue;
+        }
+        if c == ' ' {
+            continue;
+        }
+        if let Some(d) = c.to_digit(10) {
+            let m = ndigits % 2;
+            sums[1 - m] += d;
+            sums[m] += DOUBLED[d as usize];
+            reduce_sums(&mut sums, 10);
+            ndigits += 1;
+            continue;
+        }
+        return Err(non_digit(i, c));
+    }
+    if wildcards != 1 {
+        return Err(LuhnError::Wildcards(wildcards));
+    }
+    if ndigits < 2 {
+        return Err(too_short(ndigits));
+    }
+
+    let (position, k) = unknown;
+    let needed = (10 - sums[ndigits % 2] % 10) % 10;
+    let d = if (ndigits - k).is_multiple_of(2) {
+        // Invert doubling: even results come from 0..=4,
+        // odd results from 5..=9.
+        if needed % 2 == 0 {
+            needed / 2
+        } else {
+            (needed + 9) / 2
+        }
+    } else {
+        needed
+    };
+    Ok(vec![(position, char::from_digit(d, 10).unwrap())])
+}
+
+/// Serializes as the stored string.
+#[cfg(feature = "serde")]
+impl serde::Serialize for LuhnNumber {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Deserializes from a string, which must pass the check
+/// digit test.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LuhnNumber {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|e| match e {
+            LuhnError::CheckFailed => D::Error::custom("invalid Luhn check digit"),
+            e => D::Error::custom(e),
+        })
+    }
+}
+
+/// C binding for [luhn_check]. Returns `1` if `ptr` passes
+/// the check, `0` if it fails, and `-1` if `ptr` is null,
+/// not valid UTF-8, or rejected by [luhn_check] with an
+/// error.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string
+/// that stays valid and unmodified for the duration of the
+/// call.
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn luhn_check_c(ptr: *const core::ffi::c_char) -> core::ffi::c_int {
+    // Safety: contract passed on to the caller.
+    match unsafe { c_str_arg(ptr) }.map(luhn_check) {
+        Some(Ok(true)) => 1,
+        Some(Ok(false)) => 0,
+        _ => -1,
+    }
+}
+
+/// C binding for [luhn_digit_value]. Returns the check digit
+/// `0`–`9` for `ptr`, or `-1` if `ptr` is null, not valid
+/// UTF-8, or rejected by [luhn_digit_value] with an error.
+///
+/// # Safety
+///
+/// As for [luhn_check_c].
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn luhn_digit_c(ptr: *const core::ffi::c_char) -> core::ffi::c_int {
+    // Safety: contract passed on to the caller.
+    match unsafe { c_str_arg(ptr) }.map(luhn_digit_value) {
+        Some(Ok(d)) => d.into(),
+        _ => -1,
+    }
+}
+
+/// Convert a C string argument to `&str`, or `None` if it is
+/// null or not UTF-8.
+///
+/// # Safety
+///
+/// As for [luhn_check_c].
+#[cfg(feature = "ffi")]
+unsafe fn c_str_arg<'a>(ptr: *const core::ffi::c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    // Safety: `ptr` is non-null and NUL-terminated per the
+    // caller's contract.
+    unsafe { core::ffi::CStr::from_ptr(ptr) }.to_str().ok()
+}
+
+/// JavaScript binding for [luhn_check]. Errors are thrown as
+/// their `Display` message.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn luhn_check_js(s: &str) -> Result<bool, wasm_bindgen::JsValue> {
+    use std::string::ToString;
+
+    luhn_check(s).map_err(|e| wasm_bindgen::JsValue::from_str(&e.to_string()))
+}
+
+/// JavaScript binding for [luhn_digit], returning the digit
+/// as a one-character string. Errors are thrown as their
+/// `Display` message.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn luhn_digit_js(s: &str) -> Result<String, wasm_bindgen::JsValue> {
+    use std::string::ToString;
+
+    luhn_digit(s)
+        .map(String::from)
+        .map_err(|e| wasm_bindgen::JsValue::from_str(&e.to_string()))
+}
+
+/// Generate a random number of `num_digits` digits that
+/// passes the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. The first `num_digits - 1` digits are
+/// chosen uniformly at random; the last is the check digit.
+///
+/// # Panics
+///
+/// Panics if `num_digits < 2`, since no shorter number can
+/// be checked.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_generate};
+/// use rand::SeedableRng;
+///
+/// let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+/// let cc_number = luhn_generate(&mut rng, 16);
+/// assert_eq!(16, cc_number.len());
+/// assert!(luhn_check(&cc_number).unwrap());
+/// ```
+#[cfg(feature = "rand")]
+pub fn luhn_generate(rng: &mut impl rand::Rng, num_digits: usize) -> String {
+    assert!(
+        num_digits >= 2,
+        "luhn_generate: need at least 2 digits, got {}",
+        num_digits,
+    );
+    luhn_generate_with_prefix("", num_digits, rng).unwrap()
+}
+
+/// Generate a random number of `total_len` digits that
+/// starts with the digits of `prefix` and passes the [Luhn
+/// Algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm)
+/// check digit test. Spaces in `prefix` are skipped as with
+/// [luhn_check]. The digits between the prefix and the
+/// check digit are chosen uniformly at random.
+///
+/// A `total_len` below 2 is reported as `Short`. A prefix
+/// that leaves no room for the check digit is reported as
+/// `TooLong` with its number of digits.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_generate_with_prefix};
+/// use rand::SeedableRng;
+///
+/// let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+/// let cc_number = luhn_generate_with_prefix("4", 16, &mut rng).unwrap();
+/// assert!(cc_number.starts_with('4'));
+/// assert!(luhn_check(&cc_number).unwrap());
+/// ```
+#[cfg(feature = "rand")]
+pub fn luhn_generate_with_prefix(
+    prefix: impl AsRef<str>,
+    total_len: usize,
+    rng: &mut impl rand::Rng,
+) -> Result<String, LuhnError> {
+    use rand::RngExt;
+
+    let prefix = prefix.as_ref();
+
+    if total_len < 2 {
+        return Err(LuhnError::Short(total_len));
+    }
+    let (ndigits, _) = luhn_sum(prefix)?;
+    if ndigits > total_len - 1 {
+        return Err(LuhnError::TooLong(ndigits));
+    }
+    let mut result = String::with_capacity(total_len);
+    result.extend(prefix.chars().filter(|&c| c != ' '));
+    result.extend(
+        (ndigits..total_len - 1).map(|_| char::from_digit(rng.random_range(0..10), 10).unwrap()),
+    );
+    append_check_digit(&mut result)?;
+    Ok(result)
+}
+
+/// Generate `count` distinct random numbers of `num_digits`
+/// digits each, each as with [luhn_generate], in the order
+/// generated. There are only `10^(num_digits - 1)` valid
+/// numbers of that length; if `count` is more than that,
+/// all of them are returned, so the result is shorter than
+/// `count`.
+///
+/// # Panics
+///
+/// Panics if `num_digits` is less than 2.
+///
+/// # Examples
+///
+/// ```
+/// # use luhn::{luhn_check, luhn_generate_many};
+/// use rand::SeedableRng;
+///
+/// let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+/// let numbers = luhn_generate_many(&mut rng, 16, 3);
+/// assert_eq!(3, numbers.len());
+/// assert!(numbers.iter().all(|n| luhn_check(n).unwrap()));
+/// assert_eq!(10, luhn_generate_many(&mut rng, 2, 20).len());
+/// ```
+#[cfg(feature = "rand")]
+pub fn luhn_generate_many(
+    rng: &mut impl rand::Rng,
+    num_digits: usize,
+    count: usize,
+) -> Vec<String> {
+    assert!(
+        num_digits >= 2,
+        "luhn_generate_many: need at least 2 digits, got {}",
+        num_digits,
+    );
+    let possible = u32::try_from(num_digits - 1)
+        .ok()
+        .and_then(|n| 10usize.checked_pow(n))
+        .unwrap_or(usize::MAX);
+    let count = count.min(possible);
+    let mut seen = alloc::collections::BTreeSet::new();
+    let mut numbers = Vec::with_capacity(count);
+    while numbers.len() < count {
+        let cc_number = luhn_generate(rng, num_digits);
+        if seen.insert(cc_number.clone()) {
+            numbers.push(cc_number);
+        }
+    }
+    numbers
+}
+
+#[test]
+fn test_non_digit_cc_number() {
+    assert_eq!(Err(LuhnError::NonDigit(0, 'f')), luhn_check("foo"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_check("0 foo 0"));
+}
+
+#[test]
+fn test_empty_cc_number() {
+    assert_eq!(Err(LuhnError::Empty), luhn_check(""));
+    assert_eq!(Err(LuhnError::Empty), luhn_check(" "));
+    assert_eq!(Err(LuhnError::Empty), luhn_check("  "));
+}
+
+#[test]
+fn test_single_digit_cc_number() {
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check("0"));
+}
+
+#[test]
+fn test_two_digit_cc_number() {
+    assert!(luhn_check(" 0 0 ").unwrap());
+}
+
+#[test]
+fn test_valid_cc_number() {
+    assert!(luhn_check("4263 9826 4026 9299").unwrap());
+    assert!(luhn_check("4539 3195 0343 6467").unwrap());
+    assert!(luhn_check("7992 7398 713").unwrap());
+}
+
+#[test]
+fn test_invalid_cc_number() {
+    assert!(!luhn_check("4223 9826 4026 9299").unwrap());
+    assert!(!luhn_check("4539 3195 0343 6476").unwrap());
+    assert!(!luhn_check("8273 1232 7352 0569").unwrap());
+}
+
+#[test]
+fn test_error_display() {
+    use std::string::ToString;
+
+    assert_eq!(
+        "non-digit 'f' at position 2",
+        LuhnError::NonDigit(2, 'f').to_string(),
+    );
+    assert_eq!(
+        "input too short: only 1 valid digit(s)",
+        LuhnError::Short(1).to_string(),
+    );
+    assert_eq!(
+        "input too long: 16 valid digit(s)",
+        LuhnError::TooLong(16).to_string(),
+    );
+    assert_eq!(
+        "digit value 12 out of range at position 3",
+        LuhnError::BadDigit(3, 12).to_string(),
+    );
+    assert_eq!(
+        "check digit test failed",
+        LuhnError::CheckFailed.to_string(),
+    );
+    assert_eq!(
+        "wrong check digit: expected '9', found '0'",
+        LuhnError::CheckDigit {
+            expected: '9',
+            found: '0'
+        }
+        .to_string(),
+    );
+    assert_eq!(
+        "expected exactly one wildcard, found 2",
+        LuhnError::Wildcards(2).to_string(),
+    );
+    assert_eq!(
+        "expected 16 digit(s), found 15",
+        LuhnError::WrongLength {
+            expected: 16,
+            actual: 15
+        }
+        .to_string(),
+    );
+    assert_eq!(
+        "separator at edge of input, position 4",
+        LuhnError::EdgeSeparator(4).to_string(),
+    );
+    assert_eq!(
+        "repeated separator at position 4",
+        LuhnError::BadGrouping(4).to_string(),
+    );
+    assert_eq!("no digits provided", LuhnError::Empty.to_string());
+    assert_eq!(
+        "input does not match mask at position 4",
+        LuhnError::MaskMismatch(4).to_string(),
+    );
+    assert_eq!(
+        "non-ASCII digit '٣' at position 2",
+        LuhnError::NonAsciiDigit(2, '٣').to_string(),
+    );
+    assert_eq!(
+        "missing required prefix",
+        LuhnError::PrefixMismatch.to_string()
+    );
+    assert_eq!(
+        "unexpected whitespace '\\u{a0}' at position 4",
+        LuhnError::UnexpectedWhitespace(4, '\u{a0}').to_string(),
+    );
+    assert_eq!(
+        "output buffer too small: 3 byte(s) needed",
+        LuhnError::BufferTooSmall(3).to_string(),
+    );
+    assert_eq!(
+        "check digit index 4 out of range for 4 digit(s)",
+        LuhnError::CheckIndex {
+            index: 4,
+            ndigits: 4
+        }
+        .to_string(),
+    );
+    assert_eq!(
+        "no digits provided",
+        luhn_check("").unwrap_err().to_string(),
+    );
+}
+
+#[test]
+fn test_error_boxed() {
+    use std::boxed::Box;
+    use std::string::ToString;
+
+    fn parse(s: &str) -> Result<bool, Box<dyn core::error::Error>> {
+        Ok(luhn_check(s)?)
+    }
+    assert!(parse("158").unwrap());
+    assert_eq!(
+        "non-digit 'x' at position 1",
+        parse("1x8").unwrap_err().to_string(),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_complete_cc_number() {
+    assert_eq!("158", luhn_complete("15").unwrap());
+    assert_eq!("513", luhn_complete("51").unwrap());
+    assert_eq!(
+        "4263982640269299",
+        luhn_complete("4263 9826 4026 929").unwrap()
+    );
+    assert_eq!("79927398713", luhn_complete("7992 7398 71").unwrap());
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_complete("0 foo"));
+    assert_eq!(Err(LuhnError::Empty), luhn_complete(" "));
+}
+
+#[test]
+fn test_sum_cc_number() {
+    assert_eq!((0, [0, 0]), luhn_sum(" ").unwrap());
+    assert_eq!((2, [7, 2]), luhn_sum("1 5").unwrap());
+    assert_eq!((3, [4, 0]), luhn_sum("158").unwrap());
+}
+
+#[test]
+fn test_separators_cc_number() {
+    let seps = ['-', '\u{2009}'];
+    assert!(luhn_check_with_separators("4263-9826-4026-9299", &seps).unwrap());
+    let thin = "4263\u{2009}9826\u{2009}4026\u{2009}9299";
+    assert!(luhn_check_with_separators(thin, &seps).unwrap());
+    assert!(luhn_check_with_separators("-158-", &seps).unwrap());
+    assert!(!luhn_check_with_separators("-157-", &seps).unwrap());
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(2, ' ')),
+        luhn_check_with_separators("15 8", &seps)
+    );
+    assert_eq!(
+        Err(LuhnError::Empty),
+        luhn_check_with_separators("--", &seps)
+    );
+}
+
+#[test]
+fn test_bytes_cc_number() {
+    assert!(luhn_check_bytes(b"4263 9826 4026 9299").unwrap());
+    assert!(!luhn_check_bytes(b"4223 9826 4026 9299").unwrap());
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'f')),
+        luhn_check_bytes(b"0 foo 0")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_bytes(b" 0 "));
+    for s in ["158", "7992 7398 713", "8273 1232 7352 0569", "0 1x"] {
+        assert_eq!(luhn_check(s), luhn_check_bytes(s.as_bytes()));
+    }
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(1, '\t')),
+        luhn_check_bytes(b"1\t58")
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_generate_cc_number() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0x1234);
+    for i in 0..1000 {
+        let num_digits = 2 + i % 18;
+        let cc_number = luhn_generate(&mut rng, num_digits);
+        assert_eq!(num_digits, cc_number.len());
+        assert!(luhn_check(&cc_number).unwrap());
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+#[should_panic]
+fn test_generate_short_cc_number() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+    luhn_generate(&mut rng, 1);
+}
+
+#[test]
+fn test_mod_n_cc_number() {
+    let hex = |c: char| c.to_digit(16);
+    assert!(luhn_check_mod_n("A1B2C33", 16, hex).unwrap());
+    assert!(luhn_check_mod_n("0123 4567 89AB CDEF 4", 16, hex).unwrap());
+    assert!(!luhn_check_mod_n("0123 4567 89AB CDEF 5", 16, hex).unwrap());
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'G')),
+        luhn_check_mod_n("A1G", 16, hex)
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_mod_n("F", 16, hex));
+
+    // Values outside the alphabet are rejected.
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, '8')),
+        luhn_check_mod_n("18", 8, |c| c.to_digit(10))
+    );
+
+    let dec = |c: char| c.to_digit(10);
+    for s in ["158", "7992 7398 713", "4223 9826 4026 9299", "0 0"] {
+        assert_eq!(
+            luhn_check(s).unwrap(),
+            luhn_check_mod_n(s, 10, dec).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_imei() {
+    assert!(imei_check("490154203237518").unwrap());
+    assert!(imei_check("35 209900 176148 1").unwrap());
+    assert!(!imei_check("490154203237517").unwrap());
+    assert_eq!(Err(LuhnError::Short(14)), imei_check("49015420323751"));
+    assert_eq!(Err(LuhnError::TooLong(16)), imei_check("4901542032375180"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(14, 'x')),
+        imei_check("49015420323751x")
+    );
+}
+
+#[test]
+fn test_isin() {
+    assert!(isin_check("US0378331005").unwrap());
+    assert!(isin_check("us0378331005").unwrap());
+    assert!(isin_check("AU0000XVGZA3").unwrap());
+    assert!(isin_check("GB0002634946").unwrap());
+    assert!(!isin_check("US0378331006").unwrap());
+    assert_eq!(Err(LuhnError::Short(11)), isin_check("US037833100"));
+    assert_eq!(Err(LuhnError::TooLong(13)), isin_check("US03783310055"));
+    assert_eq!(Err(LuhnError::NonDigit(0, '1')), isin_check("1S0378331005"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(11, 'X')),
+        isin_check("US037833100X")
+    );
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(7, ' ')),
+        isin_check("US03783 1005")
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(7, '\u{663}')),
+        isin_check("US03783\u{663}1005")
+    );
+}
+
+#[test]
+fn test_iter_cc_number() {
+    let digits = [4, 2, 6, 3, 9, 8, 2, 6, 4, 0, 2, 6, 9, 2, 9, 9];
+    assert!(luhn_check_iter(digits).unwrap());
+    assert!(luhn_check_iter(digits.iter().copied()).unwrap());
+    assert!(!luhn_check_iter([1, 5, 7]).unwrap());
+    assert_eq!(Err(LuhnError::BadDigit(2, 10)), luhn_check_iter([1, 5, 10]));
+    assert_eq!(Err(LuhnError::Empty), luhn_check_iter([]));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_iter([0]));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_number() {
+    use std::string::ToString;
+
+    let n: LuhnNumber = "4263 9826 4026 9299".parse().unwrap();
+    assert_eq!("4263 9826 4026 9299", n.digits());
+    assert_eq!("4263 9826 4026 9299", n.to_string());
+    let m: LuhnNumber = n.to_string().parse().unwrap();
+    assert_eq!(n.digits(), m.digits());
+
+    assert!(matches!(
+        "4223 9826 4026 9299".parse::<LuhnNumber>(),
+        Err(LuhnError::CheckFailed),
+    ));
+    assert!(matches!(
+        "0 foo".parse::<LuhnNumber>(),
+        Err(LuhnError::NonDigit(2, 'f')),
+    ));
+    assert!(matches!(
+        "0".parse::<LuhnNumber>(),
+        Err(LuhnError::Short(1))
+    ));
+}
+
+#[test]
+fn test_verify_cc_number() {
+    assert!(luhn_verify("4263 9826 4026 9299").is_ok());
+    assert!(luhn_verify(" 0 0 ").is_ok());
+    assert_eq!(
+        Err(LuhnError::CheckDigit {
+            expected: '9',
+            found: '0'
+        }),
+        luhn_verify("4263 9826 4026 9290 ")
+    );
+    assert_eq!(
+        Err(LuhnError::CheckDigit {
+            expected: '5',
+            found: '6'
+        }),
+        luhn_verify("4539 3195 0343 6476")
+    );
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_verify("0 foo"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_verify("0"));
+}
+
+#[test]
+fn test_diagnose_cc_number() {
+    assert_eq!(
+        LuhnDiagnosis::Valid,
+        luhn_diagnose("4263 9826 4026 9299").unwrap(),
+    );
+    // Digits 4 and 5 swapped, across a group boundary.
+    assert_eq!(
+        LuhnDiagnosis::Transposition(4),
+        luhn_diagnose("426 9 3826 4026 9299").unwrap(),
+    );
+    // The 09/90 transposition is undetectable.
+    assert_eq!(LuhnDiagnosis::Valid, luhn_diagnose("109").unwrap());
+    assert_eq!(LuhnDiagnosis::Valid, luhn_diagnose("190").unwrap());
+    assert_eq!(
+        LuhnDiagnosis::SingleDigit {
+            position: 18,
+            digit: '9'
+        },
+        luhn_diagnose("4263 9826 4026 9295").unwrap(),
+    );
+    // Ambiguous: a transposition is preferred.
+    assert_eq!(
+        LuhnDiagnosis::Transposition(5),
+        luhn_diagnose("4263 9826 4026 9290").unwrap(),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_diagnose("0 foo"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_diagnose("0"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_solve_wildcard() {
+    assert_eq!(
+        vec![(13, '6')],
+        luhn_solve_wildcard("4263 9826 402* 9299", '*').unwrap(),
+    );
+    assert_eq!(
+        vec![(0, '4')],
+        luhn_solve_wildcard("?263 9826 4026 9299", '?').unwrap(),
+    );
+    assert_eq!(
+        vec![(18, '9')],
+        luhn_solve_wildcard("4263 9826 4026 929*", '*').unwrap(),
+    );
+    for i in 0..16 {
+        let mut masked: Vec<char> = "4539319503436467".chars().collect();
+        let expected = masked[i];
+        masked[i] = '*';
+        let masked: String = masked.into_iter().collect();
+        assert_eq!(
+            vec![(i, expected)],
+            luhn_solve_wildcard(&masked, '*').unwrap(),
+        );
+    }
+    assert_eq!(
+        Err(LuhnError::Wildcards(0)),
+        luhn_solve_wildcard("4263", '*')
+    );
+    assert_eq!(
+        Err(LuhnError::Wildcards(2)),
+        luhn_solve_wildcard("4*6*", '*')
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_solve_wildcard("*", '*'));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'x')),
+        luhn_solve_wildcard("4*x", '*')
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_append_check_digit() {
+    let mut s = String::from("15");
+    append_check_digit(&mut s).unwrap();
+    assert_eq!("158", s);
+
+    let mut s = String::from("4263 9826 4026 929 ");
+    append_check_digit(&mut s).unwrap();
+    assert_eq!("4263 9826 4026 929 9", s);
+    assert!(luhn_check(&s).unwrap());
+
+    let mut s = String::from("  ");
+    assert_eq!(Err(LuhnError::Empty), append_check_digit(&mut s));
+    assert_eq!("  ", s);
+}
+
+#[test]
+fn test_check_integer() {
+    assert!(luhn_check_u64(4263982640269299));
+    assert!(luhn_check_u64(4539319503436467));
+    assert!(luhn_check_u64(79927398713));
+    assert!(luhn_check_u64(158));
+    assert!(!luhn_check_u64(4223982640269299));
+    assert!(!luhn_check_u64(8273123273520569));
+    assert!(!luhn_check_u64(0));
+    assert!(luhn_check_u64(18));
+    assert!(luhn_check_u128(u128::from(u64::MAX) * 1000 + 3));
+    for n in [158u64, 7518, 1234567890, u64::MAX] {
+        assert_eq!(
+            luhn_check(std::format!("{}", n)).unwrap(),
+            luhn_check_u64(n),
+        );
+    }
+}
+
+#[test]
+fn test_state() {
+    let mut state = LuhnState::new();
+    assert_eq!(Err(LuhnError::Empty), state.is_valid());
+    assert_eq!(Err(LuhnError::Empty), state.check_digit());
+    state.push(' ').unwrap();
+    state.push('4').unwrap();
+    assert_eq!(Err(LuhnError::Short(1)), state.is_valid());
+    assert_eq!(Err(LuhnError::NonDigit(2, 'x')), state.push('x'));
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(3, '\t')),
+        state.push('\t')
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(4, '\u{663}')),
+        state.push('\u{663}')
+    );
+    for c in "263 9826 4026 929".chars() {
+        state.push(c).unwrap();
+    }
+    assert!(!state.is_valid().unwrap());
+    assert_eq!('9', state.check_digit().unwrap());
+    state.push('9').unwrap();
+    assert!(state.is_valid().unwrap());
+
+    for s in ["158", "7992 7398 713", "4223 9826 4026 9299"] {
+        let mut state = LuhnState::new();
+        for c in s.chars() {
+            state.push(c).unwrap();
+        }
+        assert_eq!(luhn_check(s).unwrap(), state.is_valid().unwrap());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_state_errors() {
+    // Everything built on `LuhnState` reports bad input as
+    // `luhn_check` does.
+    for s in ["1\t58", "1\u{a0}58", "15\u{663}", "1x58"] {
+        let expected = luhn_check(s).err();
+        assert_eq!(expected, luhn_check_bounded(s, 19).err());
+        assert_eq!(expected, luhn_check_parts(&[s]).err());
+        assert_eq!(expected, luhn_normalize(s).err());
+        assert_eq!(expected, luhn_clean(s).err());
+        assert_eq!(expected, luhn_split(s).err());
+        assert_eq!(expected, luhn_suggest(s).err());
+        assert_eq!(expected, luhn_digits(s, 1).err());
+        assert_eq!(expected, luhn_prefix_states(s).err());
+    }
+}
+
+#[test]
+fn test_card_brand() {
+    let brand = |s| card_brand(s).unwrap();
+    assert_eq!(Some(CardBrand::Visa), brand("4263 9826 4026 9299"));
+    assert_eq!(Some(CardBrand::Visa), brand("4222 2222 22222"));
+    assert_eq!(Some(CardBrand::Mastercard), brand("5555 5555 5555 4444"));
+    assert_eq!(Some(CardBrand::Mastercard), brand("2223 0031 2200 3222"));
+    assert_eq!(Some(CardBrand::Amex), brand("3782 822463 10005"));
+    assert_eq!(Some(CardBrand::Amex), brand("3714 496353 98431"));
+    assert_eq!(Some(CardBrand::Discover), brand("6011 1111 1111 1117"));
+    assert_eq!(Some(CardBrand::Discover), brand("6445 6445 6445 6445"));
+    assert_eq!(Some(CardBrand::Visa), brand("4012 8888 8888 1881"));
+    assert_eq!(Some(CardBrand::Visa), brand("4222 2222 2222 2222 224"));
+    assert_eq!(Some(CardBrand::Maestro), brand("6759 6498 2643 8453"));
+    assert_eq!(Some(CardBrand::Maestro), brand("5018 2000 0005"));
+    assert_eq!(Some(CardBrand::Maestro), brand("5612 3456 7890 1230"));
+    assert_eq!(Some(CardBrand::Maestro), brand("6390 0000 0000 0000 009"));
+    assert_eq!(Some(CardBrand::Other), brand("7992 7398 713"));
+    // Right prefix, wrong length.
+    assert_eq!(Some(CardBrand::Other), brand("3782 8224 6310 0052"));
+    assert_eq!(None, brand("4263 9826 4026 9290"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), card_brand("0 foo"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_format_grouped() {
+    assert_eq!(
+        "4263 9826 4026 9299",
+        format_grouped("4263982640269299", 4, ' '),
+    );
+    assert_eq!(
+        "4263 9826 4026 9299",
+        format_grouped(" 4263 9826 4026 9299 ", 4, ' '),
+    );
+    assert_eq!(
+        "378282-246310-005",
+        format_grouped("378282246310005", 6, '-'),
+    );
+    assert_eq!("158", format_grouped("1 5 8", 0, '-'));
+    assert_eq!("15", format_grouped("15", 4, ' '));
+    assert_eq!("", format_grouped(" ", 4, ' '));
+}
+
+#[test]
+fn test_digit_value() {
+    assert_eq!(Ok(8), luhn_digit_value("15"));
+    assert_eq!(Ok(3), luhn_digit_value("51"));
+    assert_eq!(Ok(8), luhn_digit_value("751"));
+    assert_eq!(Ok(0), luhn_digit_value("0"));
+    assert_eq!(Err(LuhnError::Empty), luhn_digit_value(" "));
+    assert_eq!(Err(LuhnError::NonDigit(1, 'x')), luhn_digit_value("1x"));
+    for s in ["15", "4263 9826 4026 929", "7992 7398 71"] {
+        let value = luhn_digit_value(s).unwrap();
+        assert_eq!(luhn_digit(s).unwrap(), char::from(b'0' + value));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_luhn_number_serde() {
+    use std::string::ToString;
+
+    let n: LuhnNumber = "4263 9826 4026 9299".parse().unwrap();
+    let json = serde_json::to_string(&n).unwrap();
+    assert_eq!("\"4263 9826 4026 9299\"", json);
+    let m: LuhnNumber = serde_json::from_str(&json).unwrap();
+    assert_eq!(n.digits(), m.digits());
+
+    let e = serde_json::from_str::<LuhnNumber>("\"4263 9826 4026 9290\"").unwrap_err();
+    assert!(e.to_string().contains("invalid Luhn check digit"));
+    let e = serde_json::from_str::<LuhnNumber>("\"0 foo\"").unwrap_err();
+    assert!(e.to_string().contains("non-digit 'f' at position 2"));
+    assert!(serde_json::from_str::<LuhnNumber>("158").is_err());
+}
+
+#[test]
+fn test_non_digit_byte_offset() {
+    assert_eq!(Err(LuhnError::NonDigit(0, 'é')), luhn_check("é5"));
+    assert_eq!(Err(LuhnError::NonDigit(1, 'é')), luhn_check("5é"));
+
+    let s = "1\u{2009}5x8";
+    let Err(LuhnError::NonDigit(i, c)) = luhn_check_with_separators(s, &['\u{2009}']) else {
+        panic!("expected NonDigit");
+    };
+    assert_eq!((5, 'x'), (i, c));
+    assert_eq!("x8", &s[i..]);
+
+    let mut state = LuhnState::new();
+    for c in "é1".chars() {
+        let _ = state.push(c);
+    }
+    assert_eq!(Err(LuhnError::NonDigit(3, 'y')), state.push('y'));
+}
+
+#[test]
+fn test_gs1() {
+    assert!(gs1_check("4006381333931").unwrap());
+    assert!(gs1_check("036000291452").unwrap());
+    assert!(gs1_check("9780 3064 0615 7").unwrap());
+    assert!(!gs1_check("4006381333913").unwrap());
+    assert_eq!(Ok('1'), gs1_digit("400638133393"));
+    assert_eq!(Ok('2'), gs1_digit("03600029145"));
+    assert_eq!(Err(LuhnError::NonDigit(3, 'x')), gs1_check("400x"));
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(3, '\t')),
+        gs1_check("400\t6")
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(2, '\u{663}')),
+        gs1_check("40\u{663}")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), gs1_check("4"));
+    assert_eq!(Err(LuhnError::Empty), gs1_digit(" "));
+}
+
+#[test]
+fn test_doubled_table() {
+    for d in 0..10 {
+        let doubled = 2 * d;
+        assert_eq!(doubled / 10 + doubled % 10, DOUBLED[d as usize]);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_check_all() {
+    let batch = [
+        "4263 9826 4026 9299",
+        "4223 9826 4026 9299",
+        "0 foo",
+        "7992 7398 713",
+        " ",
+    ];
+    assert_eq!(
+        vec![
+            Ok(true),
+            Ok(false),
+            Err(LuhnError::NonDigit(2, 'f')),
+            Ok(true),
+            Err(LuhnError::Empty),
+        ],
+        luhn_check_all(batch),
+    );
+    assert!(luhn_check_all(Vec::new()).is_empty());
+    assert!(!luhn_check_all_ok(batch));
+    assert!(luhn_check_all_ok([batch[0], batch[3]]));
+    assert!(luhn_check_all_ok([]));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_check_par() {
+    use std::string::ToString;
+
+    let numbers: Vec<String> = (0..10_000u64)
+        .map(|i| match i % 7 {
+            0 => "0 foo".to_string(),
+            1 => (i % 10).to_string(),
+            _ => (i * 7_919_393).to_string(),
+        })
+        .collect();
+    let numbers: Vec<&str> = numbers.iter().map(String::as_str).collect();
+    let serial = luhn_check_all(numbers.iter().copied());
+    assert_eq!(serial, luhn_check_par(&numbers));
+    assert!(serial.contains(&Ok(true)));
+    assert!(serial.contains(&Ok(false)));
+}
+
+#[test]
+fn test_strict_cc_number() {
+    for s in [
+        "4263 9826 4026 9299",
+        "4223 9826 4026 9299",
+        "7992 7398 713",
+        "0 0 18",
+    ] {
+        assert_eq!(luhn_check(s), luhn_check_strict(s));
+    }
+    assert_eq!(Ok(true), luhn_check(" 0 0 "));
+    assert_eq!(Ok(false), luhn_check_strict(" 0 0 "));
+    assert_eq!(Ok(true), luhn_check("0000 0000 0000 0000"));
+    assert_eq!(Ok(false), luhn_check_strict("0000 0000 0000 0000"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_strict("0"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_check_strict("0 foo"));
+}
+
+#[test]
+fn test_check_len() {
+    assert_eq!(Ok(true), luhn_check_len("4263 9826 4026 9299", 16));
+    assert_eq!(Ok(false), luhn_check_len("4263 9826 4026 9290", 16));
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 15,
+            actual: 16
+        }),
+        luhn_check_len("4263 9826 4026 9299", 15),
+    );
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 2,
+            actual: 0
+        }),
+        luhn_check_len("  ", 2),
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_len("0", 1));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_check_len("0 foo", 4));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_generate_with_prefix() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0x5678);
+    for _ in 0..100 {
+        let cc_number = luhn_generate_with_prefix("4", 16, &mut rng).unwrap();
+        assert_eq!(16, cc_number.len());
+        assert!(cc_number.starts_with('4'));
+        assert!(luhn_check(&cc_number).unwrap());
+    }
+    let cc_number = luhn_generate_with_prefix("4263 9826 4026 929", 16, &mut rng).unwrap();
+    assert_eq!("4263982640269299", cc_number);
+    assert_eq!(
+        Err(LuhnError::TooLong(16)),
+        luhn_generate_with_prefix("4263 9826 4026 9299", 16, &mut rng),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'x')),
+        luhn_generate_with_prefix("4x", 16, &mut rng),
+    );
+    assert_eq!(
+        Err(LuhnError::Short(1)),
+        luhn_generate_with_prefix("", 1, &mut rng),
+    );
+}
+
+#[test]
+fn test_unicode_ws_cc_number() {
+    let s = "4263\u{00A0}9826\u{00A0}4026\u{00A0}9299";
+    assert_eq!(Ok(true), luhn_check_unicode_ws(s));
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(4, '\u{00A0}')),
+        luhn_check(s)
+    );
+    assert_eq!(
+        Ok(true),
+        luhn_check_unicode_ws("\t4263 9826\u{2009}4026\u{3000}9299\n")
+    );
+    assert_eq!(
+        Ok(false),
+        luhn_check_unicode_ws("4263\u{00A0}9826\u{00A0}4026\u{00A0}9290")
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(6, '-')),
+        luhn_check_unicode_ws("4263\u{00A0}-")
+    );
+}
+
+#[test]
+fn test_check_digit_trait() {
+    use std::format;
+
+    let schemes: [&dyn CheckDigit; 4] = [&Luhn, &Gs1, &LuhnModN(16), &LuhnModN(10)];
+    for scheme in schemes {
+        for body in ["15", "4263 9826 4026 929", "4006 3813 3393"] {
+            let digit = scheme.digit(body).unwrap();
+            assert_eq!(Ok(true), scheme.check(&format!("{}{}", body, digit)));
+        }
+        assert_eq!(Err(LuhnError::NonDigit(1, '!')), scheme.check("1!"));
+        assert_eq!(Err(LuhnError::Empty), scheme.digit(""));
+    }
+
+    assert_eq!(Ok('8'), Luhn.digit("15"));
+    assert_eq!(luhn_check("7992 7398 713"), Luhn.check("7992 7398 713"));
+    assert_eq!(Ok('1'), Gs1.digit("400638133393"));
+    assert_eq!(Ok('3'), LuhnModN(16).digit("DEADBEE"));
+    assert_eq!(Ok('3'), LuhnModN(16).digit("deadbee"));
+    assert_eq!(Ok(true), LuhnModN(16).check("DEADBEE3"));
+    assert_eq!(Ok(true), LuhnModN(10).check("79927398713"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_repair_cc_number() {
+    assert_eq!(
+        Ok("4263982640269299".into()),
+        luhn_repair("4263 9826 4026 9290")
+    );
+    assert_eq!(
+        Ok("4263982640269299".into()),
+        luhn_repair("4263 9826 4026 9299")
+    );
+    assert_eq!(
+        Ok("4539319503436475".into()),
+        luhn_repair("4539 3195 0343 6476 ")
+    );
+    assert_eq!(Ok("00".into()), luhn_repair("0 7"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_repair("0"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_repair("0 foo"));
+}
+
+#[test]
+fn test_bytes_const_cc_number() {
+    const OK: bool = luhn_check_bytes_const(b"158");
+    const _: () = assert!(OK);
+    const _: () = assert!(!luhn_check_bytes_const(b"157"));
+
+    for s in [
+        "4263 9826 4026 9299",
+        "4223 9826 4026 9299",
+        "0 foo",
+        "0",
+        " ",
+        "0 0",
+    ] {
+        assert_eq!(
+            luhn_check_bytes(s.as_bytes()) == Ok(true),
+            luhn_check_bytes_const(s.as_bytes()),
+        );
+    }
+}
+
+#[test]
+fn test_npi() {
+    assert_eq!(Ok(true), npi_check("1234567893"));
+    assert_eq!(Ok(true), npi_check("12345 67893"));
+    assert_eq!(Ok(false), npi_check("1234567894"));
+    assert_eq!(Ok(true), luhn_check("808401234567893"));
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: 9
+        }),
+        npi_check("123456789"),
+    );
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: 15
+        }),
+        npi_check("808401234567893"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(3, '-')), npi_check("123-4567893"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_prefix_states() {
+    for s in [
+        "4263 9826 4026 9299",
+        "4223 9826 4026 9299",
+        "7992 7398 713",
+        "0 0 ",
+    ] {
+        let states = luhn_prefix_states(s).unwrap();
+        assert_eq!(luhn_sum(s).unwrap().0 - 1, states.len());
+        assert_eq!(luhn_check(s).unwrap(), *states.last().unwrap());
+    }
+    assert_eq!(Ok(vec![false, true]), luhn_prefix_states("1 58"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_prefix_states(" 1 "));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'f')),
+        luhn_prefix_states("0 foo")
+    );
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi() {
+    use std::ffi::CString;
+
+    let valid = CString::new("4263 9826 4026 9299").unwrap();
+    let invalid = CString::new("4223 9826 4026 9299").unwrap();
+    let bad = CString::new("0 foo").unwrap();
+    let non_utf8 = CString::new(&b"1\xff"[..]).unwrap();
+    let partial = CString::new("4263 9826 4026 929").unwrap();
+    unsafe {
+        assert_eq!(1, luhn_check_c(valid.as_ptr()));
+        assert_eq!(0, luhn_check_c(invalid.as_ptr()));
+        assert_eq!(-1, luhn_check_c(bad.as_ptr()));
+        assert_eq!(-1, luhn_check_c(non_utf8.as_ptr()));
+        assert_eq!(-1, luhn_check_c(core::ptr::null()));
+        assert_eq!(9, luhn_digit_c(partial.as_ptr()));
+        assert_eq!(-1, luhn_digit_c(bad.as_ptr()));
+        assert_eq!(-1, luhn_digit_c(core::ptr::null()));
+    }
+}
+
+#[test]
+fn test_check_reversed() {
+    let s = "4263 9826 4026 9299";
+    let reversed: std::string::String = s.chars().rev().collect();
+    assert!(luhn_check(s).unwrap());
+    assert!(luhn_check_reversed(&reversed).unwrap());
+    assert!(!luhn_check_reversed(s).unwrap());
+    assert!(!luhn_check_reversed("9929 6204 6289 3625").unwrap());
+    assert_eq!(
+        Err(LuhnError::NonDigit(6, 'x')),
+        luhn_check_reversed("992 96x")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_reversed(" 8 "));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_as_ref_str() {
+    use alloc::borrow::Cow;
+
+    let owned = String::from("4263 9826 4026 9299");
+    assert!(luhn_check(owned.clone()).unwrap());
+    assert!(luhn_check(&owned).unwrap());
+    assert!(luhn_check(Cow::Borrowed("158")).unwrap());
+    assert_eq!(Ok('8'), luhn_digit(String::from("15")));
+    assert!(luhn_check_all_ok([owned.as_str(), "158"]));
+}
+
+#[test]
+fn test_check_trimmed() {
+    assert!(luhn_check(" 0 0 ").unwrap());
+    assert_eq!(
+        Err(LuhnError::EdgeSeparator(0)),
+        luhn_check_trimmed(" 0 0 ")
+    );
+    assert_eq!(Err(LuhnError::EdgeSeparator(3)), luhn_check_trimmed("0 0 "));
+    assert!(luhn_check_trimmed("0 0").unwrap());
+    assert!(!luhn_check_trimmed("4223 9826 4026 9299").unwrap());
+    assert_eq!(Err(LuhnError::NonDigit(1, 'x')), luhn_check_trimmed("0x0"));
+}
+
+#[test]
+fn test_check_grouping() {
+    assert!(luhn_check("4263  9826 4026 9299").unwrap());
+    assert_eq!(
+        Err(LuhnError::BadGrouping(4)),
+        luhn_check_grouping("4263  9826 4026 9299"),
+    );
+    assert_eq!(
+        Err(LuhnError::BadGrouping(9)),
+        luhn_check_grouping("4263 9826   4026 9299"),
+    );
+    assert!(luhn_check_grouping(" 4263 9826 4026 9299 ").unwrap());
+    assert!(!luhn_check_grouping("4223 9826 4026 9299").unwrap());
+}
+
+#[test]
+fn test_damm() {
+    assert!(damm_check("5724").unwrap());
+    assert!(damm_check("57 24").unwrap());
+    assert!(!damm_check("5754").unwrap());
+    assert!(!damm_check("7524").unwrap());
+    assert_eq!(Ok('4'), damm_digit("572"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'x')), damm_check("57x4"));
+    assert_eq!(Err(LuhnError::Short(1)), damm_check("5"));
+    assert_eq!(Err(LuhnError::Empty), damm_digit(" "));
+}
+
+#[test]
+fn test_verhoeff() {
+    assert_eq!(Ok('3'), verhoeff_digit("236"));
+    assert_eq!(Ok('1'), verhoeff_digit("12345"));
+    assert_eq!(Ok('0'), verhoeff_digit("142857"));
+    assert!(verhoeff_check("2363").unwrap());
+    assert!(verhoeff_check("12 345 1").unwrap());
+    assert!(verhoeff_check("1428570").unwrap());
+    assert!(!verhoeff_check("2633").unwrap());
+    // Luhn misses this transposition; Verhoeff does not.
+    assert_eq!(luhn_check("109"), luhn_check("190"));
+    let digit = verhoeff_digit("10").unwrap();
+    let mut s = std::string::String::from("01");
+    s.push(digit);
+    assert!(!verhoeff_check(&s).unwrap());
+    assert_eq!(Err(LuhnError::NonDigit(2, 'x')), verhoeff_check("23x3"));
+    assert_eq!(Err(LuhnError::Short(1)), verhoeff_check("3"));
+    assert_eq!(Err(LuhnError::Empty), verhoeff_digit(""));
+}
+
+#[test]
+fn test_digit_count() {
+    assert_eq!(Ok(16), digit_count("4263 9826 4026 9299"));
+    assert_eq!(Ok(16), digit_count("4223 9826 4026 9299"));
+    assert_eq!(Ok(0), digit_count(" "));
+    assert_eq!(Err(LuhnError::NonDigit(3, '-')), digit_count("426-3"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_number_try_from() {
+    let n = LuhnNumber::try_from("4263 9826 4026 9299").unwrap();
+    assert_eq!("4263 9826 4026 9299", n.digits());
+    assert!(matches!(
+        LuhnNumber::try_from("4223 9826 4026 9299"),
+        Err(LuhnError::CheckFailed),
+    ));
+
+    let s = String::from("4263 9826 4026 9299");
+    let buf = s.as_ptr();
+    let n = LuhnNumber::try_from(s).unwrap();
+    assert_eq!(buf, n.digits().as_ptr());
+    assert!(matches!(
+        LuhnNumber::try_from(String::from("0 foo")),
+        Err(LuhnError::NonDigit(2, 'f')),
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mask() {
+    let s = "4263 9826 4026 9299";
+    assert_eq!(Ok(String::from("**** **** **** 9299")), mask(s, 4, '*'));
+    assert_eq!(Ok(String::from("#### #### #### ####")), mask(s, 0, '#'));
+    assert_eq!(Ok(String::from(s)), mask(s, 16, '*'));
+    assert_eq!(Ok(String::from(s)), mask(s, 100, '*'));
+    assert_eq!(Ok(String::from(" x5 8")), mask(" 15 8", 2, 'x'));
+    assert_eq!(Err(LuhnError::NonDigit(1, '-')), mask("4-2", 1, '*'));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_check_reader() {
+    use std::io::Cursor;
+
+    let input = Cursor::new("4263 9826 4026 9299\r\n4223 9826 4026 9299\n0 foo\n\n158");
+    assert_eq!(
+        vec![
+            Ok(true),
+            Ok(false),
+            Err(LuhnError::NonDigit(2, 'f')),
+            Err(LuhnError::Empty),
+            Ok(true),
+        ],
+        luhn_check_reader(input).unwrap(),
+    );
+    let e = luhn_check_reader(Cursor::new(b"158\n\xff\n")).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, e.kind());
+}
+
+#[test]
+fn test_check_bounded() {
+    let long = "1234567890".repeat(5);
+    assert_eq!(Err(LuhnError::TooLong(20)), luhn_check_bounded(&long, 19));
+    // The scan stops before reaching the bad char.
+    let bad = std::format!("{}x", long);
+    assert_eq!(Err(LuhnError::TooLong(20)), luhn_check_bounded(&bad, 19));
+    assert!(luhn_check_bounded("4263 9826 4026 9299", 16).unwrap());
+    assert!(!luhn_check_bounded("4223 9826 4026 9299", 19).unwrap());
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_bounded("1", 19));
+}
+
+#[test]
+fn test_aadhaar() {
+    assert!(aadhaar_check("234123412346").unwrap());
+    assert!(aadhaar_check("9999 4105 7058").unwrap());
+    assert!(!aadhaar_check("4991 1866 5246").unwrap());
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 12,
+            actual: 4
+        }),
+        aadhaar_check("2363"),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(4, '-')),
+        aadhaar_check("2341-2341-2346")
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        Ok((String::from("4263982640269299"), true)),
+        luhn_normalize(" 4263 9826 4026 9299 "),
+    );
+    assert_eq!(
+        Ok((String::from("4223982640269299"), false)),
+        luhn_normalize("4223 9826 4026 9299"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_normalize("0 foo"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_normalize(" 1 "));
+}
+
+#[test]
+fn test_check_skip() {
+    let skip = |c| c == '-' || c == ' ';
+    assert!(luhn_check_skip("4263-9826 4026-9299", skip).unwrap());
+    assert!(!luhn_check_skip("4223-9826 4026-9299", skip).unwrap());
+    assert_eq!(
+        Err(LuhnError::NonDigit(4, '.')),
+        luhn_check_skip("4263.9826", skip),
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_skip("- 1 -", skip));
+}
+
+#[test]
+fn test_expected_digit() {
+    assert_eq!(Ok('9'), luhn_expected_digit("4263 9826 4026 9290"));
+    assert_eq!(Ok('9'), luhn_expected_digit("4263 9826 4026 9299 "));
+    assert_eq!(Ok('8'), luhn_expected_digit("150"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_expected_digit("5"));
+    assert_eq!(Err(LuhnError::NonDigit(1, 'x')), luhn_expected_digit("1x0"));
+}
+
+#[test]
+fn test_validator() {
+    for s in [
+        "",
+        "1",
+        "158",
+        "157",
+        "0 foo",
+        " 0 0 ",
+        "4263 9826 4026 9299",
+        "1\u{a0}58",
+        "1\t58",
+        "15\u{663}",
+    ] {
+        assert_eq!(luhn_check(s), LuhnValidator::default().check(s));
+    }
+
+    let cards = LuhnValidator::new()
+        .separators(&['-'])
+        .require_len(16)
+        .reject_all_zero(true);
+    assert!(cards.check("4263-9826-4026-9299").unwrap());
+    assert!(!cards.check("4223-9826-4026-9299").unwrap());
+    assert!(!cards.check("0000-0000-0000-0000").unwrap());
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 16,
+            actual: 3
+        }),
+        cards.check("158"),
+    );
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(4, ' ')),
+        cards.check("4263 9826 4026 9299"),
+    );
+
+    let bounded = LuhnValidator::new().max_len(19);
+    let long = "1234567890".repeat(5);
+    assert_eq!(Err(LuhnError::TooLong(20)), bounded.check(&long));
+}
+
+#[test]
+fn test_sin() {
+    assert!(sin_check("130 692 544").unwrap());
+    assert!(sin_check("130-692-544").unwrap());
+    assert!(sin_check("193456787").unwrap());
+    assert!(!sin_check("130 692 545").unwrap());
+    // Valid check digits, but prefixes that are not issued.
+    assert!(!sin_check("046 454 286").unwrap());
+    assert!(!sin_check("800 000 002").unwrap());
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 9,
+            actual: 10
+        }),
+        sin_check("130 692 5440"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(3, '.')), sin_check("130.692.544"));
+}
+
+#[test]
+fn test_digits_iter() {
+    let mut digits = digits_iter("1 2 3");
+    assert_eq!(Some(Ok(1)), digits.next());
+    assert_eq!(Some(Ok(2)), digits.next());
+    assert_eq!(Some(Ok(3)), digits.next());
+    assert_eq!(None, digits.next());
+
+    let mut digits = digits_iter("1x2y");
+    assert_eq!(Some(Ok(1)), digits.next());
+    assert_eq!(Some(Err(LuhnError::NonDigit(1, 'x'))), digits.next());
+    assert_eq!(None, digits.next());
+    assert_eq!(None, digits.next());
+    assert_eq!(0, digits_iter(" ").count());
+
+    let mut digits = digits_iter("1\t2");
+    assert_eq!(Some(Ok(1)), digits.next());
+    assert_eq!(
+        Some(Err(LuhnError::UnexpectedWhitespace(1, '\t'))),
+        digits.next()
+    );
+    let mut digits = digits_iter("\u{663}");
+    assert_eq!(
+        Some(Err(LuhnError::NonAsciiDigit(0, '\u{663}'))),
+        digits.next()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_check_or_fill() {
+    assert_eq!(Ok((String::from("158"), true)), luhn_check_or_fill("15*"));
+    assert_eq!(
+        Ok((String::from("4263 9826 4026 9299 "), true)),
+        luhn_check_or_fill("4263 9826 4026 929* "),
+    );
+    assert_eq!(Ok((String::from("158"), true)), luhn_check_or_fill("158"));
+    assert_eq!(Ok((String::from("157"), false)), luhn_check_or_fill("157"));
+    assert_eq!(Err(LuhnError::NonDigit(1, '*')), luhn_check_or_fill("1*5*"));
+    assert_eq!(Err(LuhnError::NonDigit(1, '*')), luhn_check_or_fill("1*58"));
+}
+
+#[test]
+fn test_mod_n_digit() {
+    let to_val = |c: char| c.to_digit(16);
+    let from_val = |d| char::from_digit(d, 16).unwrap().to_ascii_uppercase();
+    for body in ["DEADBEE", "0", "F", "1234 ABCD", "FFFFFFFFFFFF"] {
+        let digit = luhn_mod_n_digit(body, 16, to_val, from_val).unwrap();
+        let mut s = std::string::String::from(body);
+        s.push(digit);
+        assert!(luhn_check_mod_n(&s, 16, to_val).unwrap(), "{}", s);
+    }
+    assert_eq!(
+        Ok('8'),
+        luhn_mod_n_digit(
+            "15",
+            10,
+            |c| c.to_digit(10),
+            |d| { char::from_digit(d, 10).unwrap() }
+        )
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'G')),
+        luhn_mod_n_digit("AG", 16, to_val, from_val),
+    );
+    assert_eq!(
+        Err(LuhnError::Empty),
+        luhn_mod_n_digit(" ", 16, to_val, from_val)
+    );
+}
+
+#[test]
+fn test_residue() {
+    assert_eq!(Ok(0), luhn_residue("4263 9826 4026 9299"));
+    assert_eq!(Ok(8), luhn_residue("4263 9826 4026 9297"));
+    for s in ["158", "157", "4223 9826 4026 9299", "1", "0 foo"] {
+        assert_eq!(luhn_check(s), luhn_residue(s).map(|r| r == 0));
+    }
+}
+
+#[test]
+fn test_check_id() {
+    assert!(luhn_check_id("0-0").unwrap());
+    assert!(luhn_check_id("4263-9826 4026-9299").unwrap());
+    assert!(!luhn_check_id("4223-9826-4026-9299").unwrap());
+    assert_eq!(Err(LuhnError::NonDigit(1, '/')), luhn_check_id("0/0"));
+    assert_eq!(Err(LuhnError::Empty), luhn_check_id("- -"));
+}
+
+#[test]
+fn test_check_arr() {
+    assert_eq!(Ok(true), luhn_check_arr([1, 5, 8]));
+    assert_eq!(Ok(false), luhn_check_arr([1, 5, 7]));
+    assert_eq!(
+        Ok(true),
+        luhn_check_arr([4, 2, 6, 3, 9, 8, 2, 6, 4, 0, 2, 6, 9, 2, 9, 9]),
+    );
+    assert_eq!(Err(LuhnError::BadDigit(1, 15)), luhn_check_arr([1, 15, 8]));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_arr([0]));
+    assert_eq!(Err(LuhnError::Empty), luhn_check_arr([]));
+}
+
+#[test]
+fn test_blind_spot() {
+    assert_eq!(Ok(true), has_luhn_blind_spot("4263 9826 4026 9299 09"));
+    assert_eq!(Ok(true), has_luhn_blind_spot("19 0"));
+    assert_eq!(Ok(true), has_luhn_blind_spot("90"));
+    assert_eq!(Ok(false), has_luhn_blind_spot("4263 9826 4026 9299"));
+    assert_eq!(Ok(false), has_luhn_blind_spot("0 8 9"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(3, 'x')),
+        has_luhn_blind_spot("09 x")
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_completions() {
+    let all = luhn_completions("4 2", 4).unwrap();
+    assert_eq!(10, all.len());
+    for (k, number) in all.iter().enumerate() {
+        assert!(number.starts_with("42"));
+        assert_eq!(char::from_digit(k as u32, 10), number.chars().nth(2));
+        assert!(luhn_check(number).unwrap());
+    }
+    assert_eq!(Ok(vec![String::from("158")]), luhn_completions("15", 3));
+    assert_eq!(100, luhn_completions("", 3).unwrap().len());
+    assert_eq!(Err(LuhnError::TooLong(3)), luhn_completions("158", 3));
+    assert_eq!(Err(LuhnError::TooLong(16)), luhn_completions("4", 16));
+    assert_eq!(Err(LuhnError::TooLong(2)), luhn_completions("12", 2));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_completions("", 1));
+    assert_eq!(Err(LuhnError::Empty), luhn_completions("", 0));
+    assert_eq!(Err(LuhnError::NonDigit(1, '-')), luhn_completions("4-", 4));
+}
+
+#[test]
+fn test_check_split() {
+    assert_eq!(Ok(true), luhn_check_split("15", '8'));
+    assert_eq!(Ok(true), luhn_check_split("4263 9826 4026 929", '9'));
+    assert_eq!(Ok(false), luhn_check_split("4263 9826 4026 929", '0'));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'x')),
+        luhn_check_split("15", 'x')
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, ' ')),
+        luhn_check_split("15", ' ')
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, '\u{663}')),
+        luhn_check_split("15", '\u{663}')
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'x')),
+        luhn_check_split("1x", '8')
+    );
+    assert_eq!(Err(LuhnError::Empty), luhn_check_split("", '0'));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_card_iin() {
+    let visa = "4263 9826 4026 9299";
+    assert_eq!(Ok(Some(String::from("426398"))), card_iin(visa, 6));
+    assert_eq!(Ok(Some(String::from("42639826"))), card_iin(visa, 8));
+    assert_eq!(
+        Ok(Some(String::from("4263982640269299"))),
+        card_iin(visa, 20)
+    );
+    assert_eq!(Ok(None), card_iin("4223 9826 4026 9299", 6));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), card_iin("0 foo", 6));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_digits() {
+    for s in ["15", "4263 9826 4026 929", "0", "7 5 1"] {
+        assert_eq!(luhn_digit(s).map(String::from), luhn_digits(s, 1));
+        let two = luhn_digits(s, 2).unwrap();
+        let mut with_first = String::from(s);
+        with_first.push_str(&two[..1]);
+        assert_eq!(Ok(true), luhn_check(&with_first));
+        let mut with_both = String::from(s);
+        with_both.push_str(&two);
+        assert_eq!(Ok(true), luhn_check(&with_both));
+    }
+    assert_eq!(Ok(String::from("86")), luhn_digits("15", 2));
+    assert_eq!(Ok(String::new()), luhn_digits("15", 0));
+    assert_eq!(Err(LuhnError::NonDigit(1, 'x')), luhn_digits("1x", 2));
+    assert_eq!(Err(LuhnError::Empty), luhn_digits(" ", 2));
+    assert_eq!(Err(LuhnError::Empty), luhn_digits("", 0));
+}
+
+#[test]
+fn test_error_hash_ord() {
+    use std::collections::HashMap;
+
+    let inputs = ["", " ", "1", "0 foo", "0 fix", "1 2 3 4x", "7"];
+    let mut counts: HashMap<LuhnError, usize> = HashMap::new();
+    for s in inputs {
+        *counts.entry(luhn_check(s).unwrap_err()).or_default() += 1;
+    }
+    assert_eq!(4, counts.len());
+    assert_eq!(2, counts[&LuhnError::Empty]);
+    assert_eq!(2, counts[&LuhnError::Short(1)]);
+    assert_eq!(2, counts[&LuhnError::NonDigit(2, 'f')]);
+    assert_eq!(1, counts[&LuhnError::NonDigit(7, 'x')]);
+
+    assert!(LuhnError::NonDigit(0, 'z') < LuhnError::Short(0));
+    assert!(LuhnError::Short(1) < LuhnError::Short(2));
+    assert!(LuhnError::NonDigit(1, 'a') < LuhnError::NonDigit(1, 'b'));
+}
+
+#[test]
+fn test_check_pos() {
+    assert_eq!(Err(LuhnError::NonDigit(2, 'x')), luhn_check_pos("0 0 x"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'x')),
+        luhn_check_pos("  0    0x")
+    );
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(1, '\u{a0}')),
+        luhn_check_pos("7\u{a0}51 8")
+    );
+    assert_eq!(Err(LuhnError::NonDigit(0, 'f')), luhn_check_pos(" foo"));
+    assert_eq!(Ok(true), luhn_check_pos("7 51 8"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_pos("  7 "));
+}
+
+#[test]
+fn test_error_code() {
+    let errors = [
+        LuhnError::NonDigit(0, 'x'),
+        LuhnError::Short(1),
+        LuhnError::TooLong(20),
+        LuhnError::BadDigit(0, 10),
+        LuhnError::CheckFailed,
+        LuhnError::CheckDigit {
+            expected: '8',
+            found: '0',
+        },
+        LuhnError::Wildcards(2),
+        LuhnError::WrongLength {
+            expected: 9,
+            actual: 8,
+        },
+        LuhnError::EdgeSeparator(0),
+        LuhnError::BadGrouping(4),
+        LuhnError::Empty,
+        LuhnError::MaskMismatch(4),
+        LuhnError::NonAsciiDigit(2, '٣'),
+        LuhnError::PrefixMismatch,
+        LuhnError::UnexpectedWhitespace(4, '\u{a0}'),
+        LuhnError::BufferTooSmall(3),
+        LuhnError::CheckIndex {
+            index: 4,
+            ndigits: 4,
+        },
+    ];
+    for (i, e) in errors.iter().enumerate() {
+        assert_eq!(i as u32 + 1, e.code());
+        assert!(LuhnError::from_code(e.code()).is_some());
+    }
+    assert_eq!(Some("non-digit"), LuhnError::from_code(1));
+    assert_eq!(Some("no digits provided"), LuhnError::from_code(11));
+    assert_eq!(Some("input does not match mask"), LuhnError::from_code(12));
+    assert_eq!(None, LuhnError::from_code(0));
+    assert_eq!(Some("non-ASCII digit"), LuhnError::from_code(13));
+    assert_eq!(Some("missing required prefix"), LuhnError::from_code(14));
+    assert_eq!(Some("unexpected whitespace"), LuhnError::from_code(15));
+    assert_eq!(Some("output buffer too small"), LuhnError::from_code(16));
+    assert_eq!(
+        Some("check digit index out of range"),
+        LuhnError::from_code(17)
+    );
+    assert_eq!(None, LuhnError::from_code(18));
+}
+
+#[test]
+fn test_check_csv_cell() {
+    assert_eq!(Ok(true), luhn_check_csv_cell("4263,9826,4026,9299"));
+    assert_eq!(Ok(true), luhn_check_csv_cell("4263\t9826\t4026\t9299"));
+    assert_eq!(Ok(true), luhn_check_csv_cell(" 4263, 9826,\t4026 9299"));
+    assert_eq!(Ok(false), luhn_check_csv_cell("4223,9826,4026,9299"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(7, 'O')),
+        luhn_check_csv_cell("4263,98O6,4026,9299"),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(4, ';')),
+        luhn_check_csv_cell("4263;9826")
+    );
+}
+
+#[test]
+fn test_luhn_macro() {
+    const CARD: &str = luhn!("4263 9826 4026 9299");
+    assert_eq!("4263 9826 4026 9299", CARD);
+    assert_eq!("158", luhn!("158"));
+    // Fails to compile: wrong check digit.
+    // let _ = luhn!("4263 9826 4026 9290");
+}
+
+#[test]
+fn test_weighted_mod10() {
+    let cases = [
+        "4263 9826 4026 9299",
+        "4223 9826 4026 9299",
+        "158",
+        "157",
+        "00",
+    ];
+    for s in cases {
+        assert_eq!(luhn_check(s), weighted_mod10_check(s, &[2, 1]));
+    }
+    // 3 * 1 + 2 * 2 + 1 * 3 + 0 = 10.
+    assert_eq!(Ok(true), weighted_mod10_check("1230", &[1, 2, 3]));
+    // 9 * 7 = 63 contributes 6 + 3.
+    assert_eq!(Ok(true), weighted_mod10_check("91", &[7]));
+    assert_eq!(Ok(false), weighted_mod10_check("1231", &[1, 2, 3]));
+    // 9 * (2^32 - 1) = 38654705655, with digit sum 54.
+    assert_eq!(Ok(true), weighted_mod10_check("96", &[u32::MAX]));
+    assert_eq!(Ok(false), weighted_mod10_check("99", &[u32::MAX]));
+    let mut long = "9".repeat(1_000_000);
+    long.push('0');
+    assert_eq!(Ok(true), weighted_mod10_check(&long, &[u32::MAX]));
+    long.pop();
+    long.push('1');
+    assert_eq!(Ok(false), weighted_mod10_check(&long, &[u32::MAX]));
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'x')),
+        weighted_mod10_check("1x", &[2])
+    );
+    assert_eq!(Err(LuhnError::Short(1)), weighted_mod10_check("1", &[2]));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_find_luhn_numbers() {
+    let text = "Card 4263 9826 4026 9299 was charged $12 on order 4223982640269299.";
+    assert_eq!(
+        vec![(5, String::from("4263982640269299"))],
+        find_luhn_numbers(text, 12),
+    );
+    // "158" passes, but is shorter than `min_len`.
+    assert!(find_luhn_numbers("code 158", 12).is_empty());
+    assert_eq!(
+        vec![(5, String::from("158"))],
+        find_luhn_numbers("code 158", 2)
+    );
+    // Runs separated only by spaces are one candidate.
+    assert!(find_luhn_numbers("158 125", 2).is_empty());
+    assert_eq!(
+        vec![(0, String::from("158")), (4, String::from("125"))],
+        find_luhn_numbers("158,125", 2),
+    );
+    assert!(find_luhn_numbers("", 2).is_empty());
+}
+
+#[test]
+fn test_checksum() {
+    assert_eq!(Ok(2), luhn_checksum("15"));
+    assert_eq!(Ok('8'), luhn_digit("15"));
+    assert_eq!(Ok(0), luhn_residue("158"));
+    for s in ["4263 9826 4026 929", "0", "7 51", "1"] {
+        let checksum = luhn_checksum(s).unwrap();
+        assert_eq!(Ok(((10 - checksum) % 10) as u8), luhn_digit_value(s));
+    }
+    assert_eq!(Ok(0), luhn_checksum("0"));
+    assert_eq!(Err(LuhnError::Empty), luhn_checksum(" "));
+}
+
+#[test]
+fn test_minimum_lengths() {
+    // A one-digit body completes to a checkable number.
+    assert_eq!(Ok('9'), luhn_digit("5"));
+    assert_eq!(Ok(true), luhn_check("59"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check("5"));
+    assert_eq!(Err(LuhnError::Empty), luhn_digit(""));
+    assert_eq!(Err(LuhnError::Empty), luhn_check(""));
+}
+
+#[test]
+fn test_gs1_check_len() {
+    assert_eq!(Ok(true), gs1_check_len("96385074", 8));
+    assert_eq!(Ok(true), gs1_check_len("036000291452", 12));
+    assert_eq!(Ok(true), gs1_check_len("4006381333931", 13));
+    assert_eq!(Ok(false), gs1_check_len("96385075", 8));
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 13,
+            actual: 12
+        }),
+        gs1_check_len("036000291452", 13),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'x')),
+        gs1_check_len("96x85074", 8)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_cow_str() {
+    use alloc::borrow::Cow;
+
+    fn normalize(s: &str) -> Cow<'_, str> {
+        if s.contains('-') {
+            Cow::Owned(s.replace('-', " "))
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    let borrowed = normalize("4263 9826 4026 9299");
+    let owned = normalize("4263-9826-4026-9299");
+    assert!(matches!(borrowed, Cow::Borrowed(_)));
+    assert!(matches!(owned, Cow::Owned(_)));
+    assert_eq!(Ok(true), luhn_check(&borrowed));
+    assert_eq!(Ok(true), luhn_check(&owned));
+    assert_eq!(Ok(true), luhn_check(owned));
+    assert_eq!(Ok('9'), luhn_digit(Cow::Borrowed("4263 9826 4026 929")));
+}
+
+#[test]
+fn test_classify() {
+    assert_eq!(Ok(LuhnClass::Valid), luhn_classify("4263 9826 4026 9299"));
+    assert_eq!(
+        Ok(LuhnClass::ValidRepeated),
+        luhn_classify("4242 4242 4242 4242")
+    );
+    assert_eq!(
+        Ok(LuhnClass::ValidRepeated),
+        luhn_classify("0000 0000 0000 0000")
+    );
+    assert_eq!(
+        Ok(LuhnClass::ValidSequential),
+        luhn_classify("3456 7890 1234 56")
+    );
+    assert_eq!(
+        Ok(LuhnClass::ValidSequential),
+        luhn_classify("3210 9876 5432 1098")
+    );
+    assert_eq!(Ok(LuhnClass::Invalid), luhn_classify("4242 4242 4242 4243"));
+    assert_eq!(Ok(LuhnClass::Invalid), luhn_classify("1234"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_classify("0 foo"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_suggest() {
+    let suggestions = luhn_suggest("4263 9826 4026 9290").unwrap();
+    assert_eq!(16, suggestions.len());
+    assert!(suggestions.contains(&String::from("4263982640269299")));
+    for s in &suggestions {
+        assert_eq!(Ok(true), luhn_check(s));
+        let diff = s
+            .chars()
+            .zip("4263982640269290".chars())
+            .filter(|(a, b)| a != b);
+        assert_eq!(1, diff.count());
+    }
+    assert_eq!(Ok(vec![String::from("158")]), luhn_suggest(" 158 "));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_suggest("0 foo"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_suggest("1"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_number_eq() {
+    use std::collections::HashSet;
+
+    let spaced: LuhnNumber = "4263 9826 4026 9299".parse().unwrap();
+    let packed: LuhnNumber = "4263982640269299".parse().unwrap();
+    let other: LuhnNumber = "158".parse().unwrap();
+    assert_eq!(spaced, packed);
+    assert_ne!(spaced, other);
+    let set: HashSet<LuhnNumber> = [spaced, packed, other].into_iter().collect();
+    assert_eq!(2, set.len());
+}
+
+#[test]
+fn test_meid_hex() {
+    assert_eq!(Ok(true), meid_check_hex("AF0123450ABCDEC"));
+    assert_eq!(Ok(true), meid_check_hex("af-01-23-45-0a-bc-de-c"));
+    assert_eq!(Ok(false), meid_check_hex("AF0123450ABCDED"));
+    assert_eq!(Ok(false), meid_check_hex("AF0123550ABCDEC"));
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 15,
+            actual: 14
+        }),
+        meid_check_hex("AF0123450ABCDE"),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'G')),
+        meid_check_hex("AFG123450ABCDEC")
+    );
+}
+
+#[test]
+fn test_check_parity() {
+    for s in ["4263 9826 4026 9299", "7518", "158", "4539 3195 0343 647"] {
+        let parity = check_parity(s).unwrap();
+        let (ndigits, sums) = luhn_sum(s).unwrap();
+        assert_eq!(ndigits % 2, parity);
+        assert_eq!(luhn_check(s), Ok(sums[parity] % 10 == 0));
+    }
+    assert_eq!(Ok(0), check_parity("4263 9826 4026 9299"));
+    assert_eq!(Ok(1), check_parity("158"));
+    assert_eq!(Err(LuhnError::Short(1)), check_parity("1"));
+}
+
+#[test]
+fn test_luhn_check_unicode_digits() {
+    assert_eq!(Ok(true), luhn_check_unicode_digits("１５８"));
+    assert_eq!(Ok(false), luhn_check_unicode_digits("１５７"));
+    assert!(matches!(
+        luhn_check("１５８"),
+        Err(LuhnError::NonAsciiDigit(0, '１'))
+    ));
+    // Arabic-Indic and Devanagari, mixed with ASCII.
+    assert_eq!(Ok(true), luhn_check_unicode_digits("٤٢٦٣ 9826 ४०२६ 9299"));
+    assert_eq!(Ok(true), luhn_check_unicode_digits("7518"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(3, 'x')),
+        luhn_check_unicode_digits("１x")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_unicode_digits("８"));
+    assert_eq!(None, unicode_digit('/'));
+    assert_eq!(None, unicode_digit(':'));
+    assert_eq!(None, unicode_digit('\u{FF1A}'));
+    assert_eq!(Some(9), unicode_digit('\u{1FBF9}'));
+}
+
+#[test]
+fn test_luhn_check_masked() {
+    let mask = "####-####-####-####";
+    assert_eq!(
+        Ok(true),
+        luhn_check_masked("4263-9826-4026-9299", mask, '#')
+    );
+    assert_eq!(
+        Ok(false),
+        luhn_check_masked("4263-9826-4026-9298", mask, '#')
+    );
+    assert_eq!(
+        Err(LuhnError::MaskMismatch(9)),
+        luhn_check_masked("4263-9826/4026-9299", mask, '#'),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(5, 'x')),
+        luhn_check_masked("4263-x826-4026-9299", mask, '#'),
+    );
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(5, '\t')),
+        luhn_check_masked("4263-\t826-4026-9299", mask, '#'),
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(5, '\u{663}')),
+        luhn_check_masked("4263-\u{663}826-4026-9299", mask, '#'),
+    );
+    assert_eq!(
+        Err(LuhnError::MaskMismatch(17)),
+        luhn_check_masked("4263-9826-4026-92", mask, '#'),
+    );
+    assert_eq!(
+        Err(LuhnError::MaskMismatch(19)),
+        luhn_check_masked("4263-9826-4026-92990", mask, '#'),
+    );
+    // A literal digit in the mask is matched, not checked.
+    assert_eq!(Ok(true), luhn_check_masked("9158", "9###", '#'));
+}
+
+#[test]
+fn test_luhn_check_fast() {
+    for s in [
+        "", " ", "  ", "0", "8", "x", "\t", "\u{663}", " 8", "8 ", "18", "158", "157", "1x", "é",
+        "7518",
+    ] {
+        assert_eq!(luhn_check(s), luhn_check_fast(s), "{:?}", s);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_inspect_card() {
+    let info = inspect_card("4263 9826 4026 9299").unwrap();
+    assert_eq!(
+        CardInfo {
+            valid: true,
+            brand: Some(CardBrand::Visa),
+            normalized: String::from("4263982640269299"),
+            length: 16,
+        },
+        info,
+    );
+    let info = inspect_card("4263 9826 4026 9298").unwrap();
+    assert!(!info.valid);
+    assert_eq!(None, info.brand);
+    assert_eq!(16, info.length);
+    assert_eq!(Err(LuhnError::NonDigit(4, 'x')), inspect_card("4263x"));
+    assert_eq!(Err(LuhnError::Short(1)), inspect_card("4"));
+}
+
+#[test]
+fn test_luhn_digit_byte_const() {
+    const D: u8 = luhn_digit_byte_const(b"15");
+    const _: () = assert!(D == 8);
+
+    for s in ["15", "751", "4263 9826 4026 929", "0", "1"] {
+        assert_eq!(luhn_digit_value(s), Ok(luhn_digit_byte_const(s.as_bytes())));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_luhn_digit_byte_const_bad() {
+    luhn_digit_byte_const(std::hint::black_box(b"1x"));
+}
+
+#[test]
+fn test_luhn_check_eu() {
+    assert_eq!(Ok(true), luhn_check_eu("4263.9826.4026.9299"));
+    assert_eq!(Ok(false), luhn_check_eu("4263.9826.4026.9298"));
+    assert_eq!(
+        Ok(true),
+        luhn_check_eu("4263\u{00A0}9826\u{00A0}4026\u{00A0}9299")
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(9, ',')),
+        luhn_check_eu("4263.9826,4026.9299"),
+    );
+}
+
+#[test]
+fn test_luhn_check_from_right() {
+    for s in [
+        "",
+        " ",
+        "7",
+        "158",
+        "157",
+        "7518",
+        "4263 9826 4026 9299",
+        "4263 9826 4026 9298",
+        "0 foo",
+        "1x2y",
+    ] {
+        assert_eq!(luhn_check(s), luhn_check_from_right(s), "{:?}", s);
+    }
+}
+
+#[test]
+fn test_non_ascii_digit() {
+    // U+0663 ARABIC-INDIC DIGIT THREE, two bytes at offset 2.
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(2, '\u{0663}')),
+        luhn_check("15\u{0663}8"),
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(2, '\u{0663}')),
+        luhn_sum("1 \u{0663}"),
+    );
+    assert_eq!(
+        Err(LuhnError::NonAsciiDigit(1, '\u{0663}')),
+        luhn_check_pos("1 \u{0663}"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(2, 'x')), luhn_check("15x8"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, '\u{00BD}')),
+        luhn_check("15\u{00BD}8")
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_complete_stream() {
+    use std::io::Cursor;
+
+    let input = Cursor::new("4263 9826 4026 929\r\n15\n\n0 foo\n751");
+    let mut output = Vec::new();
+    complete_stream(input, &mut output).unwrap();
+    assert_eq!(
+        "4263 9826 4026 9299\n158\n\n0 foo\n7518\n",
+        std::str::from_utf8(&output).unwrap(),
+    );
+    let mut output = Vec::new();
+    let e = complete_stream(Cursor::new(b"15\n\xff\n"), &mut output).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, e.kind());
+    assert_eq!(b"158\n", output.as_slice());
+}
+
+#[test]
+fn test_luhn_check_groups() {
+    let groups = &[4, 4, 4, 4];
+    assert_eq!(
+        Ok(true),
+        luhn_check_groups("4263 9826 4026 9299", groups, ' ')
+    );
+    assert_eq!(
+        Ok(false),
+        luhn_check_groups("4263 9826 4026 9298", groups, ' ')
+    );
+    assert_eq!(
+        Ok(true),
+        luhn_check_groups("4263-9826-4026-9299", groups, '-')
+    );
+    // 4-3-4-4: the separator comes a digit early.
+    assert_eq!(
+        Err(LuhnError::BadGrouping(8)),
+        luhn_check_groups("4263 982 4026 9299", groups, ' '),
+    );
+    assert_eq!(
+        Err(LuhnError::BadGrouping(4)),
+        luhn_check_groups("42639826 4026 9299", groups, ' '),
+    );
+    assert_eq!(
+        Err(LuhnError::BadGrouping(17)),
+        luhn_check_groups("4263 9826 4026 92", groups, ' '),
+    );
+    assert_eq!(
+        Err(LuhnError::BadGrouping(19)),
+        luhn_check_groups("4263 9826 4026 9299 ", groups, ' '),
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(5, 'x')),
+        luhn_check_groups("4263 x826 4026 9299", groups, ' '),
+    );
+    assert_eq!(Ok(true), luhn_check_groups("7518", &[4], ' '));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error_into_io() {
+    use std::string::ToString;
+
+    fn parse(s: &str) -> std::io::Result<bool> {
+        Ok(luhn_check(s)?)
+    }
+    assert!(parse("158").unwrap());
+    let e = parse("1x8").unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, e.kind());
+    assert!(e.to_string().contains("non-digit 'x' at position 1"));
+    assert_eq!(
+        Some(&LuhnError::NonDigit(1, 'x')),
+        e.get_ref().and_then(|e| e.downcast_ref::<LuhnError>()),
+    );
+}
+
+#[test]
+fn test_digits_remaining() {
+    assert_eq!(Ok(4), digits_remaining("4263 9826 4026", 16));
+    assert_eq!(Ok(16), digits_remaining("", 16));
+    assert_eq!(Ok(0), digits_remaining("4263 9826 4026 9299 1", 16));
+    assert_eq!(
+        Err(LuhnError::NonDigit(4, 'x')),
+        digits_remaining("4263x", 16)
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_luhn_generate_many() {
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0x1234);
+    let numbers = luhn_generate_many(&mut rng, 16, 100);
+    assert_eq!(100, numbers.len());
+    assert!(numbers
+        .iter()
+        .all(|n| n.len() == 16 && luhn_check(n).unwrap()));
+    assert_eq!(100, numbers.iter().collect::<HashSet<_>>().len());
+
+    // Only 100 valid 3-digit numbers exist.
+    let numbers = luhn_generate_many(&mut rng, 3, 500);
+    assert_eq!(100, numbers.len());
+    assert_eq!(100, numbers.iter().collect::<HashSet<_>>().len());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_explain() {
+    assert_eq!("valid", luhn_explain("4263 9826 4026 9299"));
+    assert_eq!("invalid (residue 9)", luhn_explain("4263 9826 4026 9298"));
+    assert_eq!("invalid (residue 1)", luhn_explain("4263 9826 4026 9290"));
+    assert_eq!("no digits provided", luhn_explain(""));
+    assert_eq!("input too short: only 1 valid digit(s)", luhn_explain("7"));
+    assert_eq!("non-digit 'f' at position 2", luhn_explain("0 foo"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_split() {
+    assert_eq!(
+        Ok((String::from("426398264026929"), '9')),
+        luhn_split("4263 9826 4026 9299"),
+    );
+    assert_eq!(Ok((String::from("15"), '8')), luhn_split("158"));
+    assert_eq!(
+        Err(LuhnError::CheckFailed),
+        luhn_split("4263 9826 4026 9298")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_split("1"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_split("0 foo"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_token() {
+    assert_eq!(
+        Ok(String::from("1234-5678-9012-3452")),
+        luhn_token("123456789012345")
+    );
+    assert_eq!(Ok(String::from("7518")), luhn_token("751"));
+    for body in [
+        "123456789012345",
+        "4263 9826 4026 929",
+        "1",
+        "0000-0000-0000",
+    ] {
+        let token = luhn_token(body).unwrap();
+        assert_eq!(Ok(true), luhn_check_token(&token));
+    }
+    assert_eq!(Ok(false), luhn_check_token("1234-5678-9012-3456"));
+    assert_eq!(Err(LuhnError::NonDigit(4, '/')), luhn_token("1234/5678"));
+    assert_eq!(Err(LuhnError::Empty), luhn_token("-"));
+}
+
+#[test]
+fn test_luhn_scan() {
+    assert_eq!(Ok(16), luhn_scan("4263 9826 4026 9299"));
+    assert_eq!(Ok(0), luhn_scan(""));
+    assert_eq!(Ok(1), luhn_scan(" 7 "));
+    for s in ["0 foo", "4263-9826", "15\u{0663}8", "é1"] {
+        assert_eq!(
+            luhn_check(s).unwrap_err(),
+            luhn_scan(s).unwrap_err(),
+            "{:?}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_luhn_check_interleaved() {
+    // "4263 9826 4026 9299" and "4539 3195 0343 6467".
+    let packed = "4425 6339 9381 2965 4003 2463 9624 9697";
+    assert_eq!(Ok((true, true)), luhn_check_interleaved(packed));
+    assert_eq!(Ok((true, true)), luhn_check_interleaved("7155188"));
+    assert_eq!(Ok((false, true)), luhn_check_interleaved("7155189"));
+    assert_eq!(Ok((true, false)), luhn_check_interleaved("7155198"));
+    assert_eq!(Ok((true, false)), luhn_check_interleaved("1588"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_interleaved("158"));
+    assert_eq!(Err(LuhnError::Empty), luhn_check_interleaved("1"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'f')),
+        luhn_check_interleaved("0 foo")
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_luhn_clean() {
+    let input = "4263 9826 4026 9299";
+    let digits = luhn_clean(input).unwrap();
+    assert_eq!("4263982640269299", digits);
+    // Allocated once, up front, and never grown.
+    assert_eq!(input.len(), digits.capacity());
+    assert_eq!(
+        Err(LuhnError::CheckFailed),
+        luhn_clean("4263 9826 4026 9298")
+    );
+    assert_eq!(Err(LuhnError::Empty), luhn_clean(" "));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_clean("0 foo"));
+}
+
+#[test]
+fn test_luhn_digit_bytes() {
+    assert_eq!(Ok(8), luhn_digit_bytes(b"15"));
+    for s in ["751", "4263 9826 4026 929", "0", ""] {
+        assert_eq!(luhn_digit_value(s), luhn_digit_bytes(s.as_bytes()));
+    }
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_digit_bytes(b"0 foo"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, '\u{ff}')),
+        luhn_digit_bytes(b"1\xff")
+    );
+}
+
+#[test]
+fn test_digit_sum() {
+    use std::string::ToString;
+
+    for n in 0..=99 {
+        let expected: u32 = n.to_string().chars().map(|c| c.to_digit(10).unwrap()).sum();
+        assert_eq!(expected, digit_sum(n), "{}", n);
+    }
+    for d in 0..10 {
+        assert_eq!(DOUBLED[d as usize], digit_sum(2 * d));
+    }
+    assert_eq!(57, digit_sum(u32::MAX));
+}
+
+#[test]
+fn test_luhn_check_forgiving() {
+    assert_eq!(Ok(true), luhn_check_forgiving("4O12 8888 8888 l88l"));
+    assert_eq!(Ok(true), luhn_check_forgiving("4o12 BBBB 8888 1881"));
+    assert_eq!(Ok(true), luhn_check_forgiving("4O12 8888 8888 I88I"));
+    assert_eq!(Ok(true), luhn_check_forgiving("7S18"));
+    assert_eq!(Ok(false), luhn_check_forgiving("4O12 8888 8888 l88O"));
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'Q')),
+        luhn_check_forgiving("4Q12 8888 8888 1881")
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_forgiving("O"));
+}
+
+#[test]
+fn test_luhn_check_at() {
+    let s = "4263 9826 4026 9299";
+    assert_eq!(luhn_check(s), luhn_check_at(s, 15));
+    assert_eq!(Ok(true), luhn_check_at("9426 3982 6402 6929", 0));
+    assert_eq!(Ok(false), luhn_check_at("8426 3982 6402 6929", 0));
+    for s in ["158", "157", "7518", "7519", "00"] {
+        assert_eq!(luhn_check(s), luhn_check_at(s, s.len() - 1), "{:?}", s);
+    }
+    assert_eq!(
+        Err(LuhnError::CheckIndex {
+            index: 4,
+            ndigits: 4
+        }),
+        luhn_check_at("7518", 4)
+    );
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_at("7", 0));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_check_at("0 foo", 0));
+}
+
+#[test]
+fn test_luhn_check_lines() {
+    let buf = "4263 9826 4026 9299\r\n4223 9826 4026 9299\n0 foo\n\n158";
+    let mut results = luhn_check_lines(buf);
+    assert_eq!(Some(Ok(true)), results.next());
+    assert_eq!(Some(Ok(false)), results.next());
+    assert_eq!(Some(Err(LuhnError::NonDigit(2, 'f'))), results.next());
+    assert_eq!(Some(Err(LuhnError::Empty)), results.next());
+    assert_eq!(Some(Ok(true)), results.next());
+    assert_eq!(None, results.next());
+    assert_eq!(1, luhn_check_lines("158\n").count());
+    assert_eq!(0, luhn_check_lines("").count());
+}
+
+#[test]
+fn test_space_invariance() {
+    for (number, body) in [
+        ("7518", "751"),
+        ("00", "0"),
+        ("4263982640269299", "426398264026929"),
+    ] {
+        for spaced in [
+            std::format!(" {}", number),
+            std::format!("{} ", number),
+            std::format!("  {}  ", number),
+            number.chars().flat_map(|c| [c, ' ']).collect(),
+        ] {
+            assert_eq!(luhn_sum(number), luhn_sum(&spaced), "{:?}", spaced);
+            assert_eq!(luhn_check(number), luhn_check(&spaced), "{:?}", spaced);
+        }
+        assert_eq!(luhn_digit(body), luhn_digit(std::format!("{} ", body)));
+        assert_eq!(luhn_digit(body), luhn_digit(std::format!(" {}", body)));
+    }
+    for spaces in ["", " ", "   "] {
+        assert_eq!(Err(LuhnError::Empty), luhn_check(spaces));
+        assert_eq!(Err(LuhnError::Empty), luhn_digit(spaces));
+    }
+}
+
+#[test]
+fn test_isbn10_check() {
+    assert_eq!(Ok(true), isbn10_check("0306406152"));
+    assert_eq!(Ok(true), isbn10_check("097522980X"));
+    assert_eq!(Ok(true), isbn10_check("0-9752298-0-x"));
+    assert_eq!(Ok(true), isbn10_check("0 306 40615 2"));
+    assert_eq!(Ok(false), isbn10_check("0306406153"));
+    assert_eq!(Ok(false), isbn10_check("0975229801"));
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: 9
+        }),
+        isbn10_check("030640615"),
+    );
+    assert_eq!(
+        Err(LuhnError::WrongLength {
+            expected: 10,
+            actual: 11
+        }),
+        isbn10_check("03064061520"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(0, 'X')), isbn10_check("X306406152"));
+    assert_eq!(Err(LuhnError::NonDigit(3, 'a')), isbn10_check("030a406152"));
+}
+
+#[test]
+fn test_luhn_entropy_ok() {
+    // Both pass luhn_check; only one uses 4 or more digits.
+    assert_eq!(Ok(true), luhn_entropy_ok("4263 9826 4026 9299", 4));
+    assert_eq!(Ok(false), luhn_entropy_ok("0000 0000 0000 0000", 4));
+    assert_eq!(Ok(false), luhn_entropy_ok("4242 4242 4242 4242", 4));
+    assert_eq!(Ok(true), luhn_entropy_ok("4242 4242 4242 4242", 2));
+    assert_eq!(Ok(true), luhn_entropy_ok("0000 0000 0000 0000", 0));
+    assert_eq!(Ok(false), luhn_entropy_ok("4263 9826 4026 9298", 0));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'f')),
+        luhn_entropy_ok("0 foo", 1)
+    );
+}
+
+#[test]
+fn test_luhn_partial() {
+    assert_eq!(
+        Ok(LuhnPartial {
+            count: 2,
+            even_sum: 7,
+            odd_sum: 2
+        }),
+        luhn_partial("15"),
+    );
+    for s in ["", "158", "7518", "4263 9826 4026 9299"] {
+        let p = luhn_partial(s).unwrap();
+        assert_eq!(luhn_sum(s), Ok((p.count, [p.even_sum, p.odd_sum])));
+    }
+    let p = luhn_partial("158").unwrap();
+    assert_eq!(0, p.odd_sum % 10);
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_partial("0 foo"));
+}
+
+#[test]
+fn test_luhn_minimal_fix() {
+    assert_eq!(
+        Ok(Some((18, '8', '9'))),
+        luhn_minimal_fix("4263 9826 4026 9298 ")
+    );
+    assert_eq!(Ok(Some((2, '7', '8'))), luhn_minimal_fix("157"));
+    assert_eq!(Ok(None), luhn_minimal_fix("4263 9826 4026 9299"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_minimal_fix("7"));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), luhn_minimal_fix("0 foo"));
+    // A typo anywhere is fixed at the check digit.
+    let (i, _, new) = luhn_minimal_fix("4263 9826 4025 9299").unwrap().unwrap();
+    assert_eq!(
+        Ok(true),
+        luhn_check(std::format!("4263 9826 4025 929{}", new))
+    );
+    assert_eq!(18, i);
+    for s in ["7519", "7518", "0 foo"] {
+        assert_eq!(luhn_minimal_fix(s), luhn_fix_check_digit(s));
+    }
+}
+
+#[test]
+fn test_luhn_sum_radix() {
+    for s in ["", "7518", "4263 9826 4026 9299", "0 foo"] {
+        assert_eq!(luhn_sum(s), luhn_sum_radix(s, 10));
+    }
+    let hex = |c: char| c.to_digit(16);
+    for s in ["DEADBEE3", "DEADBEE4", "dead bee3", "0F", "1a2b3c4d5e6f"] {
+        let (ndigits, sums) = luhn_sum_radix(s, 16).unwrap();
+        let valid = sums[ndigits % 2] % 16 == 0;
+        assert_eq!(luhn_check_mod_n(s, 16, hex), Ok(valid), "{:?}", s);
+    }
+    assert_eq!(Err(LuhnError::NonDigit(2, 'g')), luhn_sum_radix("0 g", 16));
+    assert_eq!(Err(LuhnError::NonDigit(1, '2')), luhn_sum_radix("12", 2));
+    let (ndigits, sums) = luhn_sum_radix("zz", 36).unwrap();
+    assert_eq!(2, ndigits);
+    // z is 35: doubled, 70 is 1 * 36 + 34, so 35. Each sum
+    // is 70, reduced mod 36.
+    assert_eq!([34, 34], sums);
+}
+
+#[test]
+#[should_panic]
+fn test_luhn_sum_radix_bad_radix() {
+    let _ = luhn_sum_radix("0", 37);
+}
+
+#[test]
+fn test_luhn_is_valid() {
+    assert!(luhn_is_valid("158"));
+    assert!(luhn_is_valid("4263 9826 4026 9299"));
+    for s in ["foo", "", " ", "1", "157", "0 foo"] {
+        assert!(!luhn_is_valid(s), "{:?}", s);
+    }
+}
+
+#[test]
+fn test_long_input() {
+    // Every 9 adds 9 to each sum, doubled or not, so a run of
+    // n nines passes exactly when n is a multiple of 10.
+    let nines = "9".repeat(1_000_000);
+    assert_eq!(Ok(true), luhn_check(&nines));
+    assert_eq!(Ok(false), luhn_check(&nines[1..]));
+    let (ndigits, sums) = luhn_sum(&nines).unwrap();
+    assert_eq!(1_000_000, ndigits);
+    assert!(sums[0] < 10 && sums[1] < 10);
+    assert_eq!(Ok('9'), luhn_digit(&nines[1..]));
+    assert_eq!(Ok(true), luhn_check_bytes(nines.as_bytes()));
+    assert_eq!(Ok(true), luhn_check_from_right(&nines));
+    assert!(luhn_check_bytes_const(nines.as_bytes()));
+    assert_eq!(9, luhn_digit_byte_const(&nines.as_bytes()[1..]));
+
+    let mut state = LuhnState::new();
+    for c in nines.chars() {
+        state.push(c).unwrap();
+    }
+    assert_eq!(Ok(true), state.is_valid());
+}
+
+#[test]
+fn test_long_iter() {
+    // 480 million nines add 4.32e9 to each sum, past
+    // `u32::MAX`, without reduction along the way.
+    let nines = core::iter::repeat_n(9, 480_000_000);
+    assert_eq!(Ok(true), luhn_check_iter(nines));
+    let nines = core::iter::repeat_n(9, 1_000_001);
+    assert_eq!(Ok(false), luhn_check_iter(nines));
+}
+#[test]
+fn test_luhn_check_prefixed() {
+    assert_eq!(Ok(true), luhn_check_prefixed("GC-12345674", "GC-"));
+    assert_eq!(Ok(true), luhn_check_prefixed("GC-12345674", "GC"));
+    assert_eq!(Ok(true), luhn_check_prefixed("GC1234 5674", "GC"));
+    assert_eq!(Ok(false), luhn_check_prefixed("GC-12345675", "GC-"));
+    assert_eq!(
+        Err(LuhnError::PrefixMismatch),
+        luhn_check_prefixed("GX-12345674", "GC-")
+    );
+    assert_eq!(
+        Err(LuhnError::PrefixMismatch),
+        luhn_check_prefixed("12345674", "GC-")
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(5, 'x')),
+        luhn_check_prefixed("GC-12x45674", "GC-")
+    );
+    assert_eq!(Err(LuhnError::Empty), luhn_check_prefixed("GC-", "GC"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_valid_next_digits() {
+    assert_eq!(Ok(vec!['8']), valid_next_digits("15"));
+    for body in ["7 51", "4263 9826 4026 929", "0"] {
+        let next = valid_next_digits(body).unwrap();
+        let passing: Vec<char> = ('0'..='9')
+            .filter(|&d| luhn_check(std::format!("{}{}", body, d)).unwrap())
+            .collect();
+        assert_eq!(passing, next);
+    }
+    assert_eq!(Err(LuhnError::Empty), valid_next_digits(""));
+    assert_eq!(Err(LuhnError::NonDigit(2, 'f')), valid_next_digits("0 foo"));
+}
+
+#[test]
+fn test_luhn_self_test() {
+    for (s, r) in SELF_TEST_CHECKS {
+        assert_eq!(r, luhn_check(s), "{:?}", s);
+    }
+    for (s, r) in SELF_TEST_DIGITS {
+        assert_eq!(r, luhn_digit(s), "{:?}", s);
+    }
+    assert!(luhn_self_test());
+}
+
+#[test]
+fn test_luhn_check_parts() {
+    assert_eq!(Ok(true), luhn_check_parts(&["4263 9826", " 4026 9299"]));
+    assert_eq!(
+        Ok(true),
+        luhn_check_parts(&["4", "263982", "", "64026929", "9"])
+    );
+    assert_eq!(Ok(false), luhn_check_parts(&["4263 9826", " 4026 9298"]));
+    assert_eq!(
+        Err(LuhnError::NonDigit(11, 'x')),
+        luhn_check_parts(&["4263 9826", " 4x26 9299"])
+    );
+    assert_eq!(Err(LuhnError::Empty), luhn_check_parts(&[]));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_check_parts(&[" ", "1 "]));
+}
+
+#[test]
+fn test_unexpected_whitespace() {
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(4, '\u{a0}')),
+        luhn_check("4263\u{a0}9826 4026 9299"),
+    );
+    assert_eq!(
+        Err(LuhnError::UnexpectedWhitespace(4, '\t')),
+        luhn_check("4263\t9826"),
+    );
+    assert_eq!(Err(LuhnError::NonDigit(4, '-')), luhn_check("4263-9826"));
+    // Whitespace that is an accepted separator is skipped.
+    assert_eq!(Ok(true), luhn_check_unicode_ws("4263\u{a0}9826 4026 9299"));
+    assert_eq!(Ok(true), luhn_check_csv_cell("4263\t9826\t4026\t9299"));
+}
+
+#[test]
+fn test_luhn_complete_into() {
+    let mut out = [0; 3];
+    assert_eq!(Ok(3), luhn_complete_into("15", &mut out));
+    assert_eq!(b"158", &out);
+
+    let mut out = [b'x'; 20];
+    assert_eq!(Ok(16), luhn_complete_into("4263 9826 4026 929", &mut out));
+    assert_eq!(b"4263982640269299xxxx", &out);
+
+    let mut out = [b'x'; 3];
+    assert_eq!(
+        Err(LuhnError::BufferTooSmall(4)),
+        luhn_complete_into("7 51", &mut out)
+    );
+    assert_eq!(b"xxx", &out);
+    assert_eq!(Err(LuhnError::Empty), luhn_complete_into(" ", &mut out));
+    assert_eq!(
+        Err(LuhnError::NonDigit(2, 'f')),
+        luhn_complete_into("0 foo", &mut out)
+    );
+}
+
+#[test]
+fn test_luhn_check_with_prefix() {
+    let numbers = [
+        "4263 9826 4026 9299",
+        "4263 9826 4026 9298",
+        "7992 7398 713",
+        "158",
+        "157",
+        "7518",
+    ];
+    for number in numbers {
+        for split in 0..=number.len() {
+            let (prefix, suffix) = number.split_at(split);
+            let prefix_sum = luhn_prefix_sum(prefix).unwrap();
+            assert_eq!(
+                luhn_check(number),
+                luhn_check_with_prefix(&prefix_sum, suffix),
+                "{:?} + {:?}",
+                prefix,
+                suffix,
+            );
+        }
+    }
+    let prefix_sum = luhn_prefix_sum("4").unwrap();
+    assert_eq!(
+        Err(LuhnError::Short(1)),
+        luhn_check_with_prefix(&prefix_sum, "")
+    );
+    assert_eq!(
+        Err(LuhnError::NonDigit(1, 'f')),
+        luhn_check_with_prefix(&prefix_sum, "1f")
+    );
+}
+
+#[test]
+fn test_luhn_status() {
+    assert_eq!(Ok(LuhnStatus::Valid), luhn_status("4263 9826 4026 9299"));
+    assert_eq!(
+        Ok(LuhnStatus::WrongCheckDigit {
+            expected: '9',
+            found: '8'
+        }),
+        luhn_status("4263 9826 4026 9298"),
+    );
+    assert_eq!(
+        Ok(LuhnStatus::WrongCheckDigit {
+            expected: '3',
+            found: '4'
+        }),
+        luhn_status("7992 7398 714"),
+    );
+    assert_eq!(Ok(LuhnStatus::Valid), luhn_status("00"));
+    assert_eq!(Err(LuhnError::Short(1)), luhn_status("4"));
+    assert_eq!(Err(LuhnError::NonDigit(1, 'x')), luhn_status("4x1"));
 }
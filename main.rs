@@ -0,0 +1,67 @@
+//! Command-line Luhn checker. Reads numbers from stdin, one
+//! per line, and reports on each.
+//!
+//! By default prints `VALID`, `INVALID` or `ERROR: …` for
+//! each line, exiting with status 1 if any line was not
+//! valid. With `--complete`, instead treats each line as a
+//! body and prints it with its check digit appended, exiting
+//! with status 1 if any line could not be completed.
+
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+use luhn::{luhn_check, luhn_complete};
+
+fn usage() -> ExitCode {
+    eprintln!("usage: luhn [--complete] < numbers");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let mut complete = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--complete" => complete = true,
+            _ => return usage(),
+        }
+    }
+
+    let stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut ok = true;
+    for line in stdin.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("luhn: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let line = line.trim_end_matches('\r');
+        let report = if complete {
+            luhn_complete(line).map_err(|e| e.to_string())
+        } else {
+            match luhn_check(line) {
+                Ok(true) => Ok("VALID".to_string()),
+                Ok(false) => {
+                    ok = false;
+                    Ok("INVALID".to_string())
+                }
+                Err(e) => Err(e.to_string()),
+            }
+        };
+        let report = report.unwrap_or_else(|e| {
+            ok = false;
+            format!("ERROR: {}", e)
+        });
+        if writeln!(stdout, "{}", report).is_err() {
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
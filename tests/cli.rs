@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run(args: &[&str], input: &str) -> (Option<i32>, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_luhn"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_check() {
+    let (code, out) = run(&[], "4263 9826 4026 9299\n7992 7398 713\n");
+    assert_eq!(Some(0), code);
+    assert_eq!("VALID\nVALID\n", out);
+
+    let (code, out) = run(&[], "158\n157\n0 foo\n0\n");
+    assert_eq!(Some(1), code);
+    assert_eq!(
+        "VALID\nINVALID\nERROR: non-digit 'f' at position 2\n\
+         ERROR: input too short: only 1 valid digit(s)\n",
+        out,
+    );
+}
+
+#[test]
+fn test_cli_complete() {
+    let (code, out) = run(&["--complete"], "15\r\n4263 9826 4026 929\n");
+    assert_eq!(Some(0), code);
+    assert_eq!("158\n4263982640269299\n", out);
+
+    let (code, out) = run(&["--complete"], "15\n\nx\n");
+    assert_eq!(Some(1), code);
+    assert_eq!(
+        "158\nERROR: no digits provided\n\
+         ERROR: non-digit 'x' at position 0\n",
+        out,
+    );
+}
+
+#[test]
+fn test_cli_usage() {
+    let (code, out) = run(&["--bogus"], "");
+    assert_eq!(Some(2), code);
+    assert_eq!("", out);
+}
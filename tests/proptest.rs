@@ -0,0 +1,45 @@
+//! Property tests over the public check and check digit
+//! functions.
+
+use luhn::{luhn_check, luhn_check_from_right, luhn_digit};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn digit_completes_valid_number(body in "[0-9]{1,40}") {
+        let digit = luhn_digit(&body).unwrap();
+        let number = format!("{}{}", body, digit);
+        prop_assert_eq!(Ok(true), luhn_check(&number));
+    }
+
+    #[test]
+    fn space_does_not_change_check(number in "[0-9]{2,40}", at in any::<prop::sample::Index>()) {
+        let i = at.index(number.len() + 1);
+        let spaced = format!("{} {}", &number[..i], &number[i..]);
+        prop_assert_eq!(luhn_check(&number), luhn_check(&spaced));
+    }
+
+    #[test]
+    fn spaces_do_not_change_check_or_digit(
+        number in "[0-9]{0,40}",
+        spaces in prop::collection::vec((any::<prop::sample::Index>(), 1..4usize), 0..10),
+    ) {
+        let mut spaced = number.clone();
+        for (at, n) in spaces {
+            let i = at.index(spaced.len() + 1);
+            spaced.insert_str(i, &" ".repeat(n));
+        }
+        prop_assert_eq!(luhn_check(&number), luhn_check(&spaced));
+        prop_assert_eq!(luhn_digit(&number), luhn_digit(&spaced));
+    }
+
+    #[test]
+    fn from_right_matches_check(number in "[0-9]{0,40}") {
+        prop_assert_eq!(luhn_check(&number), luhn_check_from_right(&number));
+    }
+
+    #[test]
+    fn from_right_matches_check_any_input(input in "[0-9 x\u{00e9}]{0,40}") {
+        prop_assert_eq!(luhn_check(&input), luhn_check_from_right(&input));
+    }
+}
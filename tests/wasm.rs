@@ -0,0 +1,23 @@
+//! Run with `wasm-pack test --node -- --features wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use luhn::{luhn_check_js, luhn_digit_js};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_check_js() {
+    assert_eq!(Ok(true), luhn_check_js("4263 9826 4026 9299"));
+    assert_eq!(Ok(false), luhn_check_js("4223 9826 4026 9299"));
+    let e = luhn_check_js("0 foo").unwrap_err();
+    assert_eq!(
+        Some("non-digit 'f' at position 2".to_string()),
+        e.as_string()
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_digit_js() {
+    assert_eq!(Ok("8".to_string()), luhn_digit_js("15"));
+    let e = luhn_digit_js(" ").unwrap_err();
+    assert_eq!(Some("no digits provided".to_string()), e.as_string());
+}